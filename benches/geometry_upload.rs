@@ -0,0 +1,271 @@
+//! compares the old per-frame `create_buffer_init` upload of unindexed triangles with the
+//! persistent, growable buffers `GPUData::upload_geometry` writes into, for a 100k-sprite scene.
+//! the new paths go through the crate's own `write_geometry_buffer`.
+//!
+//! runs on the first real adapter it finds and falls back to wgpu's noop backend, which does the
+//! buffer creation and copies on the cpu but no gpu work, so only cpu and upload cost is measured.
+//! `cargo bench --bench geometry_upload`
+
+use std::time::{Duration, Instant};
+
+use bytemuck::{Pod, Zeroable};
+use maple2d::bench::{INITIAL_GEOMETRY_BUFFER_SIZE, create_geometry_buffer, write_geometry_buffer};
+use wgpu::util::DeviceExt;
+
+const SPRITE_COUNT: usize = 100_000;
+const WARMUP_FRAMES: u32 = 10;
+const FRAMES: u32 = 200;
+
+/// same layout as `wgsl_common::structs::VertexInput`
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct Vertex {
+    pos: [f32; 2],
+    color: [f32; 4],
+    uv: [f32; 2],
+    text_uv: [f32; 2],
+}
+
+/// same layout as `wgsl_sprite::structs::SpriteInstanceInput`
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct SpriteInstance {
+    transform_x: [f32; 2],
+    transform_y: [f32; 2],
+    translation: [f32; 2],
+    region: [f32; 4],
+    color: [f32; 4],
+}
+
+const QUAD_INDICES: [u32; 6] = [0, 1, 2, 0, 2, 3];
+const SIZE: f32 = 16.0;
+const WHITE: [f32; 4] = [1.0; 4];
+
+#[derive(Clone, Copy)]
+enum Path {
+    /// every sprite as two triangles with duplicated corners, uploaded into a new buffer per frame
+    CreateBufferInit,
+    /// every sprite as four corners and six indices, written into persistent buffers
+    Indexed,
+    /// one instance per sprite, written into a persistent buffer
+    Instanced,
+}
+
+impl Path {
+    fn name(self) -> &'static str {
+        match self {
+            Self::CreateBufferInit => "old: unindexed, create_buffer_init",
+            Self::Indexed => "new: indexed, persistent buffers",
+            Self::Instanced => "new: instanced, persistent buffer",
+        }
+    }
+}
+
+fn corners(x: f32, y: f32) -> [Vertex; 4] {
+    [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)].map(|(u, v)| Vertex {
+        pos: [x + u * SIZE, y + v * SIZE],
+        color: WHITE,
+        uv: [u, v],
+        text_uv: [-1.0, 0.0],
+    })
+}
+
+struct Buffers {
+    vertices: Vec<Vertex>,
+    indices: Vec<u32>,
+    instances: Vec<SpriteInstance>,
+    vertex_buffer: wgpu::Buffer,
+    index_buffer: wgpu::Buffer,
+    instance_buffer: wgpu::Buffer,
+}
+
+impl Buffers {
+    /// starts out like the buffers `GPUData` creates
+    fn new(device: &wgpu::Device) -> Self {
+        let buffer = |label, usage| {
+            create_geometry_buffer(device, label, INITIAL_GEOMETRY_BUFFER_SIZE, usage)
+        };
+        Self {
+            vertices: vec![],
+            indices: vec![],
+            instances: vec![],
+            vertex_buffer: buffer("vertex_buffer", wgpu::BufferUsages::VERTEX),
+            index_buffer: buffer("index_buffer", wgpu::BufferUsages::INDEX),
+            instance_buffer: buffer("instance_buffer", wgpu::BufferUsages::VERTEX),
+        }
+    }
+}
+
+struct Timings {
+    build: Duration,
+    upload: Duration,
+    bytes: usize,
+}
+
+/// builds and uploads one frame's geometry, then waits for the queue so the copies are included
+fn frame(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    path: Path,
+    sprites: &[[f32; 2]],
+    buffers: &mut Buffers,
+) -> Timings {
+    let start = Instant::now();
+    buffers.vertices.clear();
+    buffers.indices.clear();
+    buffers.instances.clear();
+    match path {
+        Path::CreateBufferInit => {
+            buffers.vertices.extend(sprites.iter().flat_map(|&[x, y]| {
+                let corners = corners(x, y);
+                QUAD_INDICES.map(|i| corners[i as usize])
+            }));
+        }
+        Path::Indexed => {
+            for &[x, y] in sprites {
+                let base = buffers.vertices.len() as u32;
+                buffers.vertices.extend(corners(x, y));
+                buffers.indices.extend(QUAD_INDICES.map(|i| base + i));
+            }
+        }
+        Path::Instanced => {
+            buffers
+                .instances
+                .extend(sprites.iter().map(|&[x, y]| SpriteInstance {
+                    transform_x: [SIZE, 0.0],
+                    transform_y: [0.0, SIZE],
+                    translation: [x, y],
+                    region: [0.0, 0.0, 1.0, 1.0],
+                    color: WHITE,
+                }));
+        }
+    }
+    let build = start.elapsed();
+
+    let start = Instant::now();
+    let vertex_bytes: &[u8] = bytemuck::cast_slice(&buffers.vertices);
+    let index_bytes: &[u8] = bytemuck::cast_slice(&buffers.indices);
+    let instance_bytes: &[u8] = bytemuck::cast_slice(&buffers.instances);
+    // kept alive until the frame is submitted, like the old `Context::render`
+    let mut frame_buffer = None;
+    match path {
+        Path::CreateBufferInit => {
+            frame_buffer = Some(
+                device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: Some("Vertex Buffer"),
+                    contents: vertex_bytes,
+                    usage: wgpu::BufferUsages::VERTEX,
+                }),
+            );
+        }
+        // `upload_geometry` writes every buffer each frame, the unused ones are empty here
+        Path::Indexed | Path::Instanced => {
+            write_geometry_buffer(
+                device,
+                queue,
+                &mut buffers.vertex_buffer,
+                "vertex_buffer",
+                wgpu::BufferUsages::VERTEX,
+                vertex_bytes,
+            );
+            write_geometry_buffer(
+                device,
+                queue,
+                &mut buffers.index_buffer,
+                "index_buffer",
+                wgpu::BufferUsages::INDEX,
+                index_bytes,
+            );
+            write_geometry_buffer(
+                device,
+                queue,
+                &mut buffers.instance_buffer,
+                "instance_buffer",
+                wgpu::BufferUsages::VERTEX,
+                instance_bytes,
+            );
+        }
+    }
+    let encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+    queue.submit([encoder.finish()]);
+    device.poll(wgpu::PollType::Wait).unwrap();
+    drop(frame_buffer);
+    let upload = start.elapsed();
+
+    Timings {
+        build,
+        upload,
+        bytes: vertex_bytes.len() + index_bytes.len() + instance_bytes.len(),
+    }
+}
+
+fn request_device(backends: wgpu::Backends) -> Option<(wgpu::Adapter, wgpu::Device, wgpu::Queue)> {
+    let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor {
+        backends,
+        backend_options: wgpu::BackendOptions {
+            noop: wgpu::NoopBackendOptions {
+                enable: backends.contains(wgpu::Backends::NOOP),
+            },
+            ..Default::default()
+        },
+        ..Default::default()
+    });
+    let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+        power_preference: wgpu::PowerPreference::HighPerformance,
+        force_fallback_adapter: false,
+        compatible_surface: None,
+    }))
+    .ok()?;
+    let (device, queue) =
+        pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor::default())).ok()?;
+    Some((adapter, device, queue))
+}
+
+fn main() {
+    let (adapter, device, queue) = request_device(wgpu::Backends::PRIMARY)
+        .or_else(|| request_device(wgpu::Backends::NOOP))
+        .expect("no adapter, not even the noop backend");
+    let info = adapter.get_info();
+    println!(
+        "{SPRITE_COUNT} sprites, {FRAMES} frames on {} ({:?})",
+        info.name, info.backend
+    );
+
+    // cheap deterministic noise so every run uploads the same scene
+    let mut seed = 0x2545_f491_u32;
+    let mut rand = move || {
+        seed ^= seed << 13;
+        seed ^= seed >> 17;
+        seed ^= seed << 5;
+        seed as f32 / u32::MAX as f32
+    };
+    let mut sprites = (0..SPRITE_COUNT)
+        .map(|_| [rand() * 1280.0, rand() * 720.0])
+        .collect::<Vec<_>>();
+
+    for path in [Path::CreateBufferInit, Path::Indexed, Path::Instanced] {
+        let mut buffers = Buffers::new(&device);
+        let (mut build, mut upload, mut bytes) = (Duration::ZERO, Duration::ZERO, 0);
+        for i in 0..WARMUP_FRAMES + FRAMES {
+            // move the sprites so every frame uploads new data
+            for [x, _] in &mut sprites {
+                *x = (*x + 1.0).rem_euclid(1280.0);
+            }
+            let t = frame(&device, &queue, path, &sprites, &mut buffers);
+            if i >= WARMUP_FRAMES {
+                build += t.build;
+                upload += t.upload;
+                bytes = t.bytes;
+            }
+        }
+        let ms = |d: Duration| d.as_secs_f64() * 1000.0 / FRAMES as f64;
+        println!(
+            "{:<38} build {:>7.3} ms  upload {:>7.3} ms  total {:>7.3} ms  {:>6.2} MiB/frame",
+            path.name(),
+            ms(build),
+            ms(upload),
+            ms(build + upload),
+            bytes as f64 / (1024.0 * 1024.0),
+        );
+    }
+}
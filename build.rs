@@ -4,6 +4,9 @@ fn main() -> Result<(), Box<Error>> {
     println!("cargo::rerun-if-changed=src/render/shaders/draw.wgsl");
     println!("cargo::rerun-if-changed=src/render/shaders/common.wgsl");
     println!("cargo::rerun-if-changed=src/render/shaders/stencil.wgsl");
+    println!("cargo::rerun-if-changed=src/render/shaders/sprite.wgsl");
+    println!("cargo::rerun-if-changed=src/render/shaders/mesh.wgsl");
+    println!("cargo::rerun-if-changed=src/render/shaders/glyph.wgsl");
 
     if !std::fs::exists("src/render/shaders/out").unwrap() {
         std::fs::create_dir("src/render/shaders/out").unwrap();
@@ -14,6 +17,9 @@ fn main() -> Result<(), Box<Error>> {
         .add_shader("common.wgsl")
         .add_shader("draw.wgsl")
         .add_shader("stencil.wgsl")
+        .add_shader("sprite.wgsl")
+        .add_shader("mesh.wgsl")
+        .add_shader("glyph.wgsl")
         .output("src/render/shaders/out")
         .separate_files(true)
         .build()
@@ -6,7 +6,9 @@ use std::{
 use glam::vec2;
 use image::ImageReader;
 use itertools::Itertools;
-use maple2d::{AppState, BlendMode, CanvasKey, Color, TextureFilter, TextureKey, run_app};
+use maple2d::{
+    AppState, BlendMode, CanvasKey, Color, MapleError, TextureFilter, TextureKey, run_app,
+};
 use winit::{
     event::MouseButton,
     keyboard::{Key, KeyCode, PhysicalKey},
@@ -43,11 +45,11 @@ impl AppState for State {
     }
 }
 
-fn main() {
+fn main() -> Result<(), MapleError> {
     run_app::<State>(
         60,
         Window::default_attributes(),
         wgpu::PresentMode::AutoVsync,
         wgpu::Backends::all(),
-    );
+    )
 }
@@ -0,0 +1,118 @@
+use std::time::Instant;
+
+use maple2d::{AppState, Color, MapleError, SpriteInstance, TextureFilter, TextureKey, run_app};
+use winit::{
+    keyboard::{KeyCode, PhysicalKey},
+    window::Window,
+};
+
+const SPRITE_COUNT: usize = 100_000;
+
+struct State {
+    tex: TextureKey,
+    sprites: Vec<[f32; 4]>,
+    /// toggled with space, draws through `Canvas::sprites` instead of one quad per sprite
+    instanced: bool,
+    frames: u32,
+    draw_time: f64,
+    last_report: Instant,
+}
+
+impl AppState for State {
+    fn setup(ctx: &mut maple2d::Context) -> Self {
+        let tex = ctx
+            .load_texture_path("examples/Untitled.png", TextureFilter::Linear)
+            .unwrap();
+
+        // cheap deterministic noise so every run draws the same scene
+        let mut seed = 0x2545_f491_u32;
+        let mut rand = move || {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            seed as f32 / u32::MAX as f32
+        };
+        let sprites = (0..SPRITE_COUNT)
+            .map(|_| {
+                [
+                    rand() * 1280.0,
+                    rand() * 720.0,
+                    rand() * 2.0 - 1.0,
+                    rand() * 2.0 - 1.0,
+                ]
+            })
+            .collect();
+
+        Self {
+            tex,
+            sprites,
+            instanced: true,
+            frames: 0,
+            draw_time: 0.0,
+            last_report: Instant::now(),
+        }
+    }
+
+    fn fixed_update(&mut self, ctx: &mut maple2d::Context) {
+        if ctx.is_key_just_pressed(PhysicalKey::Code(KeyCode::Space)) {
+            self.instanced = !self.instanced;
+        }
+        if ctx.is_key_just_pressed(PhysicalKey::Code(KeyCode::KeyV)) {
+            let vsync = ctx.present_mode() != wgpu::PresentMode::AutoNoVsync;
+            ctx.set_vsync(!vsync);
+        }
+        if ctx.is_key_just_pressed(PhysicalKey::Code(KeyCode::Escape)) {
+            ctx.exit();
+        }
+        for [x, y, vx, vy] in &mut self.sprites {
+            *x = (*x + *vx).rem_euclid(1280.0);
+            *y = (*y + *vy).rem_euclid(720.0);
+        }
+    }
+
+    fn draw(&mut self, canvas: &mut maple2d::Canvas) {
+        let start = Instant::now();
+
+        canvas.fill_color = Color::rgb(0.0, 0.0, 0.0);
+        canvas.clear();
+
+        if self.instanced {
+            let instances = self
+                .sprites
+                .iter()
+                .map(|&[x, y, ..]| SpriteInstance::centered(x, y, 8.0, 8.0, 0.0))
+                .collect::<Vec<_>>();
+            canvas.sprites(self.tex, &instances);
+        } else {
+            canvas.set_texture(self.tex);
+            for &[x, y, ..] in &self.sprites {
+                canvas.texture().xy(x, y).wh(8.0, 8.0).centered().draw();
+            }
+        }
+
+        self.draw_time += start.elapsed().as_secs_f64();
+        self.frames += 1;
+
+        let elapsed = self.last_report.elapsed().as_secs_f64();
+        if elapsed >= 1.0 {
+            println!(
+                "{SPRITE_COUNT} sprites ({}): {:.1} fps, {:.2} ms cpu draw per frame",
+                if self.instanced { "instanced" } else { "quads" },
+                self.frames as f64 / elapsed,
+                self.draw_time / self.frames as f64 * 1000.0,
+            );
+            self.frames = 0;
+            self.draw_time = 0.0;
+            self.last_report = Instant::now();
+        }
+    }
+}
+
+fn main() -> Result<(), MapleError> {
+    run_app::<State>(
+        60,
+        Window::default_attributes(),
+        wgpu::PresentMode::AutoNoVsync,
+        wgpu::Backends::all(),
+    )
+}
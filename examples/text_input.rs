@@ -0,0 +1,194 @@
+use maple2d::{
+    AppState, Color, Context, MapleError, TextInput, TextPath, TextSpan, VerticalAlign,
+    cosmic_text, glam, run_app,
+};
+use winit::{
+    event::{KeyEvent, MouseButton},
+    keyboard::{Key, NamedKey},
+    window::Window,
+};
+
+struct State {
+    name: TextInput,
+    notes: TextInput,
+    submitted: String,
+}
+
+impl AppState for State {
+    fn setup(ctx: &mut Context) -> Self {
+        let name = TextInput::new(ctx, 20.0, false);
+        let mut notes = TextInput::new(ctx, 16.0, true);
+        notes.set_text(ctx, "multi line notes\nwith undo, redo and clipboard");
+        Self {
+            name,
+            notes,
+            submitted: String::new(),
+        }
+    }
+
+    fn fixed_update(&mut self, _ctx: &mut Context) {}
+
+    fn update(&mut self, _dt: f32, ctx: &mut Context) {
+        self.name.update(ctx);
+        self.notes.update(ctx);
+    }
+
+    fn draw(&mut self, canvas: &mut maple2d::Canvas) {
+        canvas.fill_color = Color::rgb(0.1, 0.1, 0.12);
+        canvas.clear();
+
+        canvas.draw_stroke = false;
+        canvas.fill_color = Color::rgb(0.2, 0.2, 0.24);
+        canvas.rect().xywh(20.0, 20.0, 300.0, 32.0).draw();
+        canvas.rect().xywh(20.0, 80.0, 300.0, 200.0).draw();
+
+        self.name.draw(canvas, 26.0, 24.0, 288.0, 24.0);
+        self.notes.draw(canvas, 26.0, 84.0, 288.0, 192.0);
+
+        canvas.fill_color = Color::rgb(1.0, 1.0, 1.0);
+        let spans = [
+            TextSpan::new("submitted: ").bold(),
+            TextSpan::new(self.submitted.as_str())
+                .color(Color::rgb(0.5, 0.8, 1.0))
+                .underline(),
+        ];
+        canvas.rich_text(&spans).xy(20.0, 300.0).draw();
+        let hint = TextSpan::parse_markup(
+            "[b]enter[/b] submits, [color=#8f8]ctrl+z[/color] undoes, [i]drag[/i] selects",
+        );
+        canvas.rich_text(&hint).xy(20.0, 330.0).size(14.0).draw();
+
+        // highlights the word under the mouse
+        let mouse = canvas.ctx().mouse_pos();
+        let words = "hover over any of these words";
+        let mut text = canvas.text(words).xy(20.0, 360.0);
+        if let Some(cursor) = text.hit_test(mouse) {
+            let start = words[..cursor.index].rfind(' ').map_or(0, |i| i + 1);
+            let end = words[cursor.index..]
+                .find(' ')
+                .map_or(words.len(), |i| cursor.index + i);
+            let rects = text.selection_rects(
+                cosmic_text::Cursor::new(0, start)..cosmic_text::Cursor::new(0, end),
+            );
+            canvas.fill_color = Color::rgb(0.3, 0.3, 0.5);
+            for (pos, size) in rects {
+                canvas.rect().xywh(pos.x, pos.y, size.x, size.y).draw();
+            }
+        }
+        canvas.fill_color = Color::rgb(1.0, 1.0, 1.0);
+        canvas.text(words).xy(20.0, 360.0).draw();
+
+        canvas.fill_color = Color::rgb(1.0, 0.9, 0.4);
+        canvas
+            .text("notes")
+            .xy(340.0, 20.0)
+            .size(32.0)
+            .outline(2.0, Color::rgb(0.0, 0.0, 0.0))
+            .shadow(glam::vec2(3.0, 3.0), 4.0, Color::rgba(0.0, 0.0, 0.0, 0.6))
+            .glow(6.0, Color::rgba(1.0, 0.6, 0.1, 0.5))
+            .draw();
+
+        // distance field glyphs stay sharp while scaled and rotated
+        let wave = canvas.ctx().elapsed_time().sin() as f32;
+        canvas.with_state(|canvas| {
+            canvas.translate(420.0, 220.0);
+            canvas.rotate(wave * 0.3);
+            canvas.scale(1.0 + wave * 0.5, 1.0 + wave * 0.5);
+            canvas.fill_color = Color::rgb(0.6, 0.9, 1.0);
+            canvas
+                .text("sdf")
+                .xy(-24.0, -16.0)
+                .size(32.0)
+                .sdf(true)
+                .outline(1.5, Color::rgb(0.1, 0.2, 0.4))
+                .draw();
+        });
+
+        // a label running around a circle and one following a zigzag line
+        canvas.fill_color = Color::rgb(0.9, 0.9, 0.9);
+        canvas
+            .text_on_path(
+                "around and around",
+                TextPath::Arc {
+                    center: glam::vec2(600.0, 220.0),
+                    radius: 50.0,
+                    start_angle: -std::f32::consts::FRAC_PI_2 + wave,
+                },
+            )
+            .align(cosmic_text::Align::Center)
+            .sdf(true)
+            .draw();
+        let zigzag = [
+            glam::vec2(20.0, 460.0),
+            glam::vec2(120.0, 420.0),
+            glam::vec2(220.0, 460.0),
+            glam::vec2(320.0, 420.0),
+        ];
+        canvas
+            .text_on_path("following the line", TextPath::Polyline(&zigzag))
+            .align(cosmic_text::Align::Center)
+            .baseline(4.0)
+            .draw();
+
+        // the notes cut down to a small box
+        let notes = self.notes.text();
+        canvas.fill_color = Color::rgb(0.2, 0.2, 0.24);
+        canvas.rect().xywh(340.0, 80.0, 160.0, 60.0).draw();
+        canvas.fill_color = Color::rgb(0.8, 0.8, 0.8);
+        canvas
+            .text(&notes)
+            .xywh(340.0, 80.0, 160.0, 60.0)
+            .size(14.0)
+            .valign(VerticalAlign::Center)
+            .ellipsis(true)
+            .clip(true)
+            .draw();
+    }
+
+    fn key_event(&mut self, event: KeyEvent, ctx: &mut Context) {
+        if !self.name.key_event(&event, ctx)
+            && self.name.is_focused()
+            && event.state.is_pressed()
+            && event.logical_key == Key::Named(NamedKey::Enter)
+        {
+            self.submitted = self.name.text();
+            self.name.set_text(ctx, "");
+        }
+        self.notes.key_event(&event, ctx);
+
+        // cycles the default font through the installed families
+        if event.state.is_pressed() && event.logical_key == Key::Named(NamedKey::F2) {
+            let families = ctx.font_families();
+            let next = match ctx.default_font_family() {
+                cosmic_text::Family::Name(name) => families.iter().position(|f| f == name),
+                _ => None,
+            }
+            .map_or(0, |i| i + 1);
+            ctx.set_default_font_family(families.get(next).map(String::as_str));
+        }
+    }
+
+    fn mouse_input(&mut self, button: MouseButton, pressed: bool, ctx: &mut Context) {
+        self.name.mouse_input(button, pressed, ctx);
+        self.notes.mouse_input(button, pressed, ctx);
+    }
+
+    fn text_input(&mut self, text: &str, ctx: &mut Context) {
+        self.name.text_input(text, ctx);
+        self.notes.text_input(text, ctx);
+    }
+
+    fn ime_preedit(&mut self, text: &str, cursor: Option<(usize, usize)>, ctx: &mut Context) {
+        self.name.ime_preedit(text, cursor, ctx);
+        self.notes.ime_preedit(text, cursor, ctx);
+    }
+}
+
+fn main() -> Result<(), MapleError> {
+    run_app::<State>(
+        60,
+        Window::default_attributes(),
+        wgpu::PresentMode::AutoVsync,
+        wgpu::Backends::all(),
+    )
+}
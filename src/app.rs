@@ -1,6 +1,6 @@
 use std::collections::HashSet;
 use std::sync::Arc;
-use std::time::{Duration, Instant};
+use std::time::Instant;
 
 use ahash::{AHashMap, AHashSet};
 use glam::{Vec2, vec2};
@@ -9,14 +9,19 @@ use slotmap::SlotMap;
 use wgpu::SurfaceTexture;
 use winit::application::ApplicationHandler;
 use winit::dpi::PhysicalSize;
-use winit::event::{DeviceEvent, WindowEvent};
+use winit::event::{DeviceEvent, Ime, WindowEvent};
 use winit::event_loop::{ActiveEventLoop, ControlFlow, EventLoop, EventLoopProxy};
+use winit::keyboard::ModifiersState;
 use winit::window::{Window, WindowAttributes, WindowId};
 
 use crate::CanvasKey;
 use crate::context::{
     CanvasContext, Context, ContextRunMode, EitherKey, MouseWheelInfo, PressInfo,
+    debug::{DebugOverlay, draw_debug_overlay},
+    stats::FrameStats,
+    time::FrameTime,
 };
+use crate::error::MapleError;
 use crate::render::GPUData;
 use crate::state::AppState;
 
@@ -32,29 +37,91 @@ struct App<S> {
     data: Option<AppData<S>>,
     present_mode: wgpu::PresentMode,
     backends: wgpu::Backends,
+    fixed_update_rate: u32,
     proxy: Arc<EventLoopProxy<CustomEvent>>,
     output: Arc<Mutex<Option<SurfaceTexture>>>,
     do_resize: Option<PhysicalSize<u32>>,
+    /// set when startup fails, returned from `run_app` once the event loop exits
+    error: Option<MapleError>,
 }
 
 enum CustomEvent {
-    FixedUpdate,
     Render,
+    /// getting the next surface texture failed
+    SurfaceError(wgpu::SurfaceError),
+}
+
+impl<S: AppState> AppData<S> {
+    /// runs every fixed tick that is due, called whenever the event loop is idle and before rendering
+    fn run_fixed_updates(&mut self) {
+        self.ctx.advance_fixed_time();
+        while self.ctx.take_fixed_tick() {
+            self.ctx.run_mode = ContextRunMode::Fixed;
+            self.state.fixed_update(&mut self.ctx);
+            self.ctx.run_mode = ContextRunMode::None;
+
+            self.ctx.fixed_tick += 1;
+            self.ctx.debug_overlay.record_fixed_tick();
+        }
+    }
+}
+
+impl<S: AppState> App<S> {
+    /// rebuilds the gpu state if the device was lost, exiting the event loop if that fails
+    fn recover_lost_device(&mut self, event_loop: &ActiveEventLoop) {
+        let Some(data) = &mut self.data else {
+            return;
+        };
+        if !data.ctx.gpu_data.is_device_lost() {
+            return;
+        }
+        // a pending surface texture belongs to the old device
+        drop(self.output.lock().take());
+        match data.ctx.recover_device() {
+            Ok(()) => {
+                data.state.device_restored(&mut data.ctx);
+                data.ctx.window.request_redraw();
+            }
+            Err(e) => {
+                self.error = Some(e);
+                event_loop.exit();
+            }
+        }
+    }
 }
 
 impl<S: AppState> ApplicationHandler<CustomEvent> for App<S> {
     fn resumed(&mut self, event_loop: &ActiveEventLoop) {
-        let window = Arc::new(
-            event_loop
-                .create_window(self.attrs.take().unwrap())
-                .unwrap(),
-        );
+        let Some(attrs) = self.attrs.take() else {
+            return;
+        };
+        let window = match event_loop.create_window(attrs) {
+            Ok(window) => Arc::new(window),
+            Err(e) => {
+                self.error = Some(MapleError::WindowCreation(e));
+                event_loop.exit();
+                return;
+            }
+        };
 
-        let mut gpu_data = pollster::block_on(GPUData::new(
-            window.clone(),
-            self.backends,
-            self.present_mode,
-        ));
+        let mut backends = self.backends;
+        let mut tried = wgpu::Backends::empty();
+        let mut gpu_data = loop {
+            match pollster::block_on(GPUData::new(window.clone(), backends, self.present_mode)) {
+                Ok(gpu_data) => break gpu_data,
+                Err(e) => {
+                    tried |= backends;
+                    match S::fallback_backends(&e, tried) {
+                        Some(fallback) if !tried.contains(fallback) => backends = fallback,
+                        _ => {
+                            self.error = Some(e);
+                            event_loop.exit();
+                            return;
+                        }
+                    }
+                }
+            }
+        };
         gpu_data.resize(window.inner_size().width, window.inner_size().height);
 
         let mut ctx = Context {
@@ -62,16 +129,33 @@ impl<S: AppState> ApplicationHandler<CustomEvent> for App<S> {
             gpu_data,
             canvas_datas: SlotMap::default(),
             loaded_textures: SlotMap::default(),
+            meshes: SlotMap::default(),
+            exit_requested: false,
+            virtual_resolution: None,
             mouse_pos: vec2(0.0, 0.0),
             current_canvas: None,
             passes: vec![],
             vertices: vec![],
+            indices: vec![],
+            sprite_instances: vec![],
+            glyph_instances: vec![],
+            mesh_instances: vec![],
             buffer_cache: AHashMap::new(),
+            default_font_family: None,
             render_frame: 0,
             fixed_tick: 0,
             key_info: AHashMap::new(),
             mouse_button_info: AHashMap::new(),
             run_mode: ContextRunMode::None,
+            frame_stats: FrameStats::default(),
+            current_stats: FrameStats::default(),
+            last_gpu_time: None,
+            debug_overlay: DebugOverlay::default(),
+            time: FrameTime::new(self.fixed_update_rate),
+            ime_allowed: false,
+            ime_composing: false,
+            modifiers: ModifiersState::empty(),
+            clipboard: None,
             mouse_wheel_info: MouseWheelInfo {
                 delta: Vec2::ZERO,
                 render_frame: None,
@@ -97,11 +181,47 @@ impl<S: AppState> ApplicationHandler<CustomEvent> for App<S> {
         };
         match event {
             WindowEvent::CloseRequested => {
-                drop(self.output.lock().take());
-                event_loop.exit();
+                if data.state.close_requested(&mut data.ctx) {
+                    drop(self.output.lock().take());
+                    event_loop.exit();
+                }
             }
             WindowEvent::Resized(to) => {
                 self.do_resize = Some(to);
+                data.state.resized(to.width, to.height, &mut data.ctx);
+            }
+            WindowEvent::Focused(focused) => {
+                data.state.focus_changed(focused, &mut data.ctx);
+            }
+            WindowEvent::CursorEntered { .. } => {
+                data.state.cursor_entered(&mut data.ctx);
+            }
+            WindowEvent::CursorLeft { .. } => {
+                data.state.cursor_left(&mut data.ctx);
+            }
+            WindowEvent::Occluded(occluded) => {
+                data.state.occluded(occluded, &mut data.ctx);
+            }
+            WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                data.state.scale_factor_changed(scale_factor, &mut data.ctx);
+            }
+            WindowEvent::Ime(ime) => match ime {
+                Ime::Preedit(text, cursor) => {
+                    data.ctx.ime_composing = !text.is_empty();
+                    data.state.ime_preedit(&text, cursor, &mut data.ctx);
+                }
+                Ime::Commit(text) => {
+                    data.ctx.ime_composing = false;
+                    data.state.text_input(&text, &mut data.ctx);
+                }
+                Ime::Disabled if data.ctx.ime_composing => {
+                    data.ctx.ime_composing = false;
+                    data.state.ime_preedit("", None, &mut data.ctx);
+                }
+                Ime::Enabled | Ime::Disabled => (),
+            },
+            WindowEvent::DroppedFile(path) => {
+                data.state.file_dropped(path, &mut data.ctx);
             }
             WindowEvent::RedrawRequested => {
                 // println!("Redraw requested");
@@ -113,11 +233,13 @@ impl<S: AppState> ApplicationHandler<CustomEvent> for App<S> {
                     if output.is_some() {
                         return;
                     }
-                    let Ok(o) = surface.get_current_texture() else {
-                        return;
-                    };
-                    *output = Some(o);
-                    _ = proxy.send_event(CustomEvent::Render);
+                    match surface.get_current_texture() {
+                        Ok(o) => {
+                            *output = Some(o);
+                            _ = proxy.send_event(CustomEvent::Render);
+                        }
+                        Err(e) => _ = proxy.send_event(CustomEvent::SurfaceError(e)),
+                    }
                 });
             }
             WindowEvent::KeyboardInput { event, .. } => {
@@ -125,6 +247,12 @@ impl<S: AppState> ApplicationHandler<CustomEvent> for App<S> {
                     let k1 = EitherKey::Physical(event.physical_key);
                     let k2 = EitherKey::Logical(event.logical_key.clone());
                     if event.state.is_pressed() {
+                        if let Some(hotkey) = &data.ctx.debug_overlay.hotkey
+                            && (*hotkey == k1 || *hotkey == k2)
+                        {
+                            data.ctx.debug_overlay.visible = !data.ctx.debug_overlay.visible;
+                        }
+
                         for k in [k1, k2] {
                             let info = data.ctx.key_info.entry(k).or_insert(PressInfo {
                                 pressed: false,
@@ -153,7 +281,19 @@ impl<S: AppState> ApplicationHandler<CustomEvent> for App<S> {
                     }
                 }
 
+                let text = event
+                    .text
+                    .as_ref()
+                    .filter(|_| event.state.is_pressed() && !data.ctx.ime_composing)
+                    .map(|text| text.replace(char::is_control, ""))
+                    .filter(|text| !text.is_empty());
                 data.state.key_event(event, &mut data.ctx);
+                if let Some(text) = text {
+                    data.state.text_input(&text, &mut data.ctx);
+                }
+            }
+            WindowEvent::ModifiersChanged(modifiers) => {
+                data.ctx.modifiers = modifiers.state();
             }
             WindowEvent::CursorMoved { position, .. } => {
                 // let elapsed = data.last.elapsed().as_secs_f64();
@@ -228,18 +368,23 @@ impl<S: AppState> ApplicationHandler<CustomEvent> for App<S> {
         // }
     }
 
+    fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
+        self.recover_lost_device(event_loop);
+        if let Some(data) = &mut self.data {
+            data.run_fixed_updates();
+            if data.ctx.exit_requested {
+                drop(self.output.lock().take());
+                event_loop.exit();
+            }
+        }
+    }
+
     fn user_event(&mut self, event_loop: &ActiveEventLoop, event: CustomEvent) {
+        self.recover_lost_device(event_loop);
         let Some(data) = &mut self.data else {
             return;
         };
         match event {
-            CustomEvent::FixedUpdate => {
-                data.ctx.run_mode = ContextRunMode::Fixed;
-                data.state.fixed_update(&mut data.ctx);
-                data.ctx.run_mode = ContextRunMode::None;
-
-                data.ctx.fixed_tick += 1;
-            }
             CustomEvent::Render => {
                 let output_ref = &mut *self.output.lock();
 
@@ -247,18 +392,41 @@ impl<S: AppState> ApplicationHandler<CustomEvent> for App<S> {
                     return;
                 };
                 // println!("render");
+                data.run_fixed_updates();
                 data.ctx.reset_draw();
+                data.ctx.advance_frame_time();
+                let dt = data.ctx.delta_time();
+                if dt > 0.0 {
+                    data.ctx.debug_overlay.record_frame(dt);
+                }
 
                 data.ctx.run_mode = ContextRunMode::Render;
+                data.state.update(dt, &mut data.ctx);
+                let target = data
+                    .ctx
+                    .virtual_resolution
+                    .as_ref()
+                    .map(|v| v.canvas)
+                    .unwrap_or(data.main_canvas);
+                let draw_start = Instant::now();
                 CanvasContext {
                     inner: &mut data.ctx,
                 }
-                .draw_canvas(data.main_canvas, |canvas| {
+                .draw_canvas(target, |canvas| {
                     data.state.draw(canvas);
                 });
+                data.ctx.current_stats.draw_time = draw_start.elapsed();
+                data.ctx.composite_virtual(data.main_canvas);
+                if data.ctx.debug_overlay.visible {
+                    CanvasContext {
+                        inner: &mut data.ctx,
+                    }
+                    .draw_canvas(data.main_canvas, draw_debug_overlay);
+                }
                 data.ctx.run_mode = ContextRunMode::None;
                 data.ctx.render_frame += 1;
 
+                data.ctx.sort_layers();
                 data.ctx.render(output);
                 data.ctx.window.request_redraw();
 
@@ -267,7 +435,29 @@ impl<S: AppState> ApplicationHandler<CustomEvent> for App<S> {
                     data.ctx
                         .resize_canvas(data.main_canvas, to.width, to.height);
                     self.do_resize = None;
+                } else if data.ctx.gpu_data.surface_outdated {
+                    data.ctx.gpu_data.reconfigure_surface();
+                }
+                if data.ctx.exit_requested {
+                    event_loop.exit();
+                }
+            }
+            CustomEvent::SurfaceError(e) => {
+                match e {
+                    wgpu::SurfaceError::Lost | wgpu::SurfaceError::Outdated => {
+                        data.ctx.gpu_data.reconfigure_surface();
+                    }
+                    wgpu::SurfaceError::OutOfMemory => {
+                        data.ctx
+                            .gpu_data
+                            .device_lost
+                            .store(true, std::sync::atomic::Ordering::Relaxed);
+                        self.recover_lost_device(event_loop);
+                        return;
+                    }
+                    wgpu::SurfaceError::Timeout | wgpu::SurfaceError::Other => (),
                 }
+                data.ctx.window.request_redraw();
             }
         }
     }
@@ -278,29 +468,26 @@ pub fn run_app<S: AppState>(
     window_attributes: WindowAttributes,
     present_mode: wgpu::PresentMode,
     backends: wgpu::Backends,
-) {
-    let event_loop = EventLoop::with_user_event().build().unwrap();
+) -> Result<(), MapleError> {
+    let event_loop = EventLoop::with_user_event().build()?;
 
     event_loop.set_control_flow(ControlFlow::Poll);
 
-    let proxy = event_loop.create_proxy();
-    std::thread::spawn(move || {
-        loop {
-            let Ok(_) = proxy.send_event(CustomEvent::FixedUpdate) else {
-                break;
-            };
-            spin_sleep::sleep(Duration::from_secs_f64(1.0 / fixed_update_rate as f64));
-        }
-    });
-
     let mut app = App::<S> {
         data: None,
         attrs: Some(window_attributes),
         present_mode,
         backends,
+        fixed_update_rate,
         proxy: Arc::new(event_loop.create_proxy()),
         output: Arc::new(Mutex::new(None)),
         do_resize: None,
+        error: None,
     };
-    event_loop.run_app(&mut app).unwrap();
+    event_loop.run_app(&mut app)?;
+
+    match app.error {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
 }
@@ -0,0 +1,132 @@
+use glam::{Affine2, Vec2, vec2};
+
+use crate::{Canvas, Context};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Camera2D {
+    /// world position shown at the center of the viewport
+    pub position: Vec2,
+    pub zoom: f32,
+    pub rotation: f32,
+
+    /// top left corner of the viewport on the canvas
+    pub viewport_pos: Vec2,
+    pub viewport_size: Vec2,
+
+    /// world area (min, max) the view is kept inside of
+    pub bounds: Option<(Vec2, Vec2)>,
+
+    pub(crate) shake_intensity: f32,
+    pub(crate) shake_duration: f32,
+    pub(crate) shake_time: f32,
+    pub(crate) shake_offset: Vec2,
+}
+
+impl Camera2D {
+    pub fn new(viewport_width: f32, viewport_height: f32) -> Self {
+        Self {
+            position: Vec2::ZERO,
+            zoom: 1.0,
+            rotation: 0.0,
+            viewport_pos: Vec2::ZERO,
+            viewport_size: vec2(viewport_width, viewport_height),
+            bounds: None,
+            shake_intensity: 0.0,
+            shake_duration: 0.0,
+            shake_time: 0.0,
+            shake_offset: Vec2::ZERO,
+        }
+    }
+
+    /// world to screen transform
+    pub fn transform(&self) -> Affine2 {
+        Affine2::from_translation(self.viewport_pos + self.viewport_size / 2.0)
+            * Affine2::from_angle(-self.rotation)
+            * Affine2::from_scale(Vec2::splat(self.zoom))
+            * Affine2::from_translation(-(self.position + self.shake_offset))
+    }
+    pub fn world_to_screen(&self, point: Vec2) -> Vec2 {
+        self.transform().transform_point2(point)
+    }
+    pub fn screen_to_world(&self, point: Vec2) -> Vec2 {
+        self.transform().inverse().transform_point2(point)
+    }
+    pub fn mouse_world_pos(&self, ctx: &Context) -> Vec2 {
+        self.screen_to_world(ctx.mouse_pos())
+    }
+    /// the (min, max) world area currently in view, ignoring rotation
+    pub fn visible_area(&self) -> (Vec2, Vec2) {
+        let half = self.viewport_size / 2.0 / self.zoom;
+        (self.position - half, self.position + half)
+    }
+
+    /// zooms by `factor` while keeping the world point under `screen_point` in place
+    pub fn zoom_at(&mut self, screen_point: Vec2, factor: f32) {
+        let before = self.screen_to_world(screen_point);
+        self.zoom *= factor;
+        let after = self.screen_to_world(screen_point);
+        self.position += before - after;
+        self.clamp_to_bounds();
+    }
+    /// moves by a screen space offset, e.g. the mouse movement while dragging
+    pub fn pan_screen(&mut self, delta: Vec2) {
+        self.position -= Vec2::from_angle(self.rotation).rotate(delta) / self.zoom;
+        self.clamp_to_bounds();
+    }
+    /// moves towards `target`, a higher `speed` catches up quicker
+    pub fn follow(&mut self, target: Vec2, speed: f32, dt: f32) {
+        self.position += (target - self.position) * (1.0 - (-speed * dt).exp());
+        self.clamp_to_bounds();
+    }
+    pub fn shake(&mut self, intensity: f32, duration: f32) {
+        self.shake_intensity = intensity;
+        self.shake_duration = duration;
+        self.shake_time = 0.0;
+    }
+    /// advances the camera shake
+    pub fn update(&mut self, dt: f32) {
+        if self.shake_time >= self.shake_duration {
+            self.shake_offset = Vec2::ZERO;
+            return;
+        }
+        self.shake_time += dt;
+
+        let t = self.shake_time;
+        let falloff = (1.0 - t / self.shake_duration).max(0.0);
+        let noise = vec2(
+            (t * 61.0).sin() + (t * 23.0).sin() * 0.5,
+            (t * 53.0).cos() + (t * 29.0).cos() * 0.5,
+        ) / 1.5;
+        self.shake_offset = noise * self.shake_intensity * falloff * falloff / self.zoom;
+    }
+    pub fn clamp_to_bounds(&mut self) {
+        let Some((min, max)) = self.bounds else {
+            return;
+        };
+        let half = self.viewport_size / 2.0 / self.zoom;
+        let clamp_axis = |v: f32, min: f32, max: f32, half: f32| {
+            if max - min < half * 2.0 {
+                (min + max) / 2.0
+            } else {
+                v.clamp(min + half, max - half)
+            }
+        };
+        self.position = vec2(
+            clamp_axis(self.position.x, min.x, max.x, half.x),
+            clamp_axis(self.position.y, min.y, max.y, half.y),
+        );
+    }
+}
+
+impl<'a> Canvas<'a> {
+    /// appends the camera's world to screen transform to the current transform
+    pub fn apply_camera(&mut self, camera: &Camera2D) {
+        self.add_transform(camera.transform());
+    }
+    pub fn with_camera<R, F: FnOnce(&mut Canvas) -> R>(&mut self, camera: &Camera2D, cb: F) -> R {
+        self.with_state(|canvas| {
+            canvas.apply_camera(camera);
+            cb(canvas)
+        })
+    }
+}
@@ -39,6 +39,31 @@ impl Color {
     pub fn rgba8(r: u8, g: u8, b: u8, a: u8) -> Self {
         Self::from_array8([r, g, b, a])
     }
+    /// parses `#rgb`, `#rgba`, `#rrggbb` or `#rrggbbaa`, the `#` is optional
+    pub fn hex(hex: &str) -> Option<Self> {
+        let hex = hex.strip_prefix('#').unwrap_or(hex);
+        if !hex.is_ascii() {
+            return None;
+        }
+        let channel = |i: usize, len: usize| {
+            let v = u8::from_str_radix(&hex[i * len..(i + 1) * len], 16).ok()?;
+            Some(if len == 1 { v * 17 } else { v })
+        };
+        let (len, count) = match hex.len() {
+            3 => (1, 3),
+            4 => (1, 4),
+            6 => (2, 3),
+            8 => (2, 4),
+            _ => return None,
+        };
+        let a = if count == 4 { channel(3, len)? } else { 255 };
+        Some(Self::rgba8(
+            channel(0, len)?,
+            channel(1, len)?,
+            channel(2, len)?,
+            a,
+        ))
+    }
 
     #[inline]
     pub fn hsv(h: f32, s: f32, v: f32) -> Self {
@@ -106,3 +131,49 @@ impl Color {
         self.a = to as f32 / 255.0;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_lengths() {
+        assert_eq!(Color::hex("#f80"), Some(Color::rgb8(0xff, 0x88, 0x00)));
+        assert_eq!(
+            Color::hex("#f804"),
+            Some(Color::rgba8(0xff, 0x88, 0x00, 0x44))
+        );
+        assert_eq!(Color::hex("#12ab9f"), Some(Color::rgb8(0x12, 0xab, 0x9f)));
+        assert_eq!(
+            Color::hex("#12ab9f80"),
+            Some(Color::rgba8(0x12, 0xab, 0x9f, 0x80))
+        );
+    }
+
+    #[test]
+    fn hex_optional_hash_and_case() {
+        assert_eq!(Color::hex("f80"), Color::hex("#f80"));
+        assert_eq!(Color::hex("12AB9F80"), Color::hex("#12ab9f80"));
+    }
+
+    #[test]
+    fn hex_invalid() {
+        for hex in [
+            "",
+            "#",
+            "##f80",
+            "f",
+            "#ff",
+            "#fffff",
+            "#fffffff",
+            "#fffffffff",
+            "#ggg",
+            "#12ab9z",
+            "#+fff",
+            "#ffé",
+            "#ééé",
+        ] {
+            assert_eq!(Color::hex(hex), None, "{hex}");
+        }
+    }
+}
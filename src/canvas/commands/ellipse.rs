@@ -57,7 +57,7 @@ impl<'a, 'r> EllipseBuilder<'a, 'r> {
     pub fn draw(self) {
         let point_count = self.canvas.arc_segments * 4;
 
-        let mut points = (0..point_count).map(|v| {
+        let points = (0..point_count).map(|v| {
             let angle = 2.0 * PI / point_count as f32 * v as f32;
             vec2(
                 self.x + self.w * angle.cos() / 2.0,
@@ -67,23 +67,17 @@ impl<'a, 'r> EllipseBuilder<'a, 'r> {
         let points2 = points.clone();
 
         if self.canvas.draw_fill {
-            let anchor = points.next().unwrap();
-            for (b, c) in points.tuple_windows() {
-                self.canvas.raw_tri(
-                    anchor,
-                    b,
-                    c,
-                    self.canvas.fill_color,
-                    self.canvas.fill_color,
-                    self.canvas.fill_color,
-                    vec2(-1.0, 0.0),
-                    vec2(-1.0, 0.0),
-                    vec2(-1.0, 0.0),
-                    vec2(-1.0, 0.0),
-                    vec2(-1.0, 0.0),
-                    vec2(-1.0, 0.0),
-                );
-            }
+            let fill_color = self.canvas.fill_color;
+            let vertices = points
+                .map(|p| {
+                    self.canvas
+                        .vertex(p, fill_color, vec2(-1.0, 0.0), vec2(-1.0, 0.0))
+                })
+                .collect_vec();
+            self.canvas.raw_indexed(
+                vertices,
+                (1..point_count as u32 - 1).flat_map(|i| [0, i, i + 1]),
+            );
         }
         if self.canvas.draw_stroke {
             self.canvas.draw_stroke(points2);
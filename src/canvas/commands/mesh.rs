@@ -0,0 +1,59 @@
+use crate::{
+    Canvas, Color,
+    context::{DrawCall, DrawCallType, mesh::MeshKey},
+    render::shaders::wgsl_mesh,
+};
+
+#[must_use = "this command does nothing until you call `draw()`"]
+pub struct MeshBuilder<'a, 'r> {
+    pub(crate) canvas: &'r mut Canvas<'a>,
+    pub(crate) mesh: MeshKey,
+    pub(crate) tint: bool,
+}
+impl<'a, 'r> MeshBuilder<'a, 'r> {
+    /// multiplies the mesh's vertex colors by `fill_color`
+    #[inline]
+    pub fn tint(mut self) -> Self {
+        self.tint = true;
+        self
+    }
+    pub fn draw(self) {
+        let t = self.canvas.transform;
+        let color = if self.tint {
+            self.canvas.fill_color
+        } else {
+            Color::rgb(1.0, 1.0, 1.0)
+        };
+        let instance = self.canvas.ctx.inner.mesh_instances.len() as u32;
+        self.canvas
+            .ctx
+            .inner
+            .mesh_instances
+            .push(wgsl_mesh::structs::MeshInstanceInput::new(
+                t.matrix2.x_axis.to_array(),
+                t.matrix2.y_axis.to_array(),
+                t.translation.to_array(),
+                color.to_array(),
+            ));
+
+        self.canvas
+            .ctx
+            .inner
+            .passes
+            .last_mut()
+            .unwrap()
+            .calls
+            .push(DrawCall {
+                start_index: self.canvas.ctx.inner.indices.len() as u32,
+                typ: DrawCallType::Mesh {
+                    blend_mode: self.canvas.current_blend_mode,
+                    texture: self.canvas.current_texture,
+                    reference: self.canvas.stencil_reference,
+                    layer: self.canvas.current_layer,
+                    mesh: self.mesh,
+                    instance,
+                },
+            });
+        self.canvas.push_draw_call();
+    }
+}
@@ -1,5 +1,8 @@
 pub mod ellipse;
+pub mod mesh;
 pub mod rect;
+pub mod rich_text;
 pub mod text;
+pub mod text_path;
 pub mod texture;
 pub mod triangle;
@@ -1,6 +1,6 @@
 use glam::{Vec2, vec2};
 
-use crate::Canvas;
+use crate::{Canvas, canvas::QUAD_INDICES};
 
 #[must_use = "this command does nothing until you call `draw()`"]
 pub struct RectBuilder<'a, 'r> {
@@ -57,34 +57,12 @@ impl<'a, 'r> RectBuilder<'a, 'r> {
         let c = vec2(self.x + self.w, self.y + self.h);
         let d = vec2(self.x, self.y + self.h);
         if self.canvas.draw_fill {
-            self.canvas.raw_tri(
-                a,
-                b,
-                c,
-                self.canvas.fill_color,
-                self.canvas.fill_color,
-                self.canvas.fill_color,
-                vec2(-1.0, 0.0),
-                vec2(-1.0, 0.0),
-                vec2(-1.0, 0.0),
-                vec2(-1.0, 0.0),
-                vec2(-1.0, 0.0),
-                vec2(-1.0, 0.0),
-            );
-            self.canvas.raw_tri(
-                a,
-                c,
-                d,
-                self.canvas.fill_color,
-                self.canvas.fill_color,
-                self.canvas.fill_color,
-                vec2(-1.0, 0.0),
-                vec2(-1.0, 0.0),
-                vec2(-1.0, 0.0),
-                vec2(-1.0, 0.0),
-                vec2(-1.0, 0.0),
-                vec2(-1.0, 0.0),
-            );
+            let fill_color = self.canvas.fill_color;
+            let vertices = [a, b, c, d].map(|p| {
+                self.canvas
+                    .vertex(p, fill_color, vec2(-1.0, 0.0), vec2(-1.0, 0.0))
+            });
+            self.canvas.raw_indexed(vertices, QUAD_INDICES);
         }
         if self.canvas.draw_stroke {
             self.canvas.draw_stroke([a, b, c, d].into_iter());
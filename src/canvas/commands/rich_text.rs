@@ -0,0 +1,562 @@
+use std::{borrow::Cow, ops::Range};
+
+use glam::{Vec2, vec2};
+
+use crate::{
+    Canvas, Color,
+    canvas::QUAD_INDICES,
+    canvas::commands::text::get_and_shape_buffer,
+    context::fonts::default_family,
+    render::text::{find_closest_attrs, text_buffer_dimensions},
+};
+
+/// a piece of text with its own style, anything left as `None` uses the `RichTextBuilder`'s style
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextSpan<'a> {
+    pub text: Cow<'a, str>,
+    /// `None` uses the canvas' `fill_color`
+    pub color: Option<Color>,
+    pub size: Option<f32>,
+    pub family: Option<cosmic_text::Family<'a>>,
+    pub weight: Option<cosmic_text::Weight>,
+    pub style: Option<cosmic_text::Style>,
+    pub underline: bool,
+    pub strikethrough: bool,
+    /// highlights the span's line boxes
+    pub background: Option<Color>,
+}
+
+impl<'a> TextSpan<'a> {
+    #[inline]
+    pub fn new(text: impl Into<Cow<'a, str>>) -> Self {
+        Self {
+            text: text.into(),
+            color: None,
+            size: None,
+            family: None,
+            weight: None,
+            style: None,
+            underline: false,
+            strikethrough: false,
+            background: None,
+        }
+    }
+    #[inline]
+    pub fn color(mut self, v: Color) -> Self {
+        self.color = Some(v);
+        self
+    }
+    #[inline]
+    pub fn size(mut self, v: f32) -> Self {
+        self.size = Some(v);
+        self
+    }
+    #[inline]
+    pub fn family(mut self, v: cosmic_text::Family<'a>) -> Self {
+        self.family = Some(v);
+        self
+    }
+    #[inline]
+    pub fn weight(mut self, v: cosmic_text::Weight) -> Self {
+        self.weight = Some(v);
+        self
+    }
+    #[inline]
+    pub fn style(mut self, v: cosmic_text::Style) -> Self {
+        self.style = Some(v);
+        self
+    }
+    #[inline]
+    pub fn bold(self) -> Self {
+        self.weight(cosmic_text::Weight::BOLD)
+    }
+    #[inline]
+    pub fn italic(self) -> Self {
+        self.style(cosmic_text::Style::Italic)
+    }
+    #[inline]
+    pub fn underline(mut self) -> Self {
+        self.underline = true;
+        self
+    }
+    #[inline]
+    pub fn strikethrough(mut self) -> Self {
+        self.strikethrough = true;
+        self
+    }
+    #[inline]
+    pub fn background(mut self, v: Color) -> Self {
+        self.background = Some(v);
+        self
+    }
+
+    /// parses bbcode-like markup into spans. supported tags are `[b]`, `[i]`, `[u]`, `[s]`,
+    /// `[color=#rrggbb]`, `[bg=#rrggbb]`, `[size=20]` and `[font=name]`, each closed with `[/tag]`.
+    /// tags nest, `[[` is a literal `[` and anything that isn't a known tag is kept as text
+    pub fn parse_markup(markup: &'a str) -> Vec<TextSpan<'a>> {
+        let mut spans = vec![];
+        // open tags as (name, value), the style is rebuilt from them whenever they change
+        let mut open_tags: Vec<(&'a str, &'a str)> = vec![];
+        let mut text = String::new();
+        let mut rest = markup;
+
+        let style_of = |tags: &[(&'a str, &'a str)]| {
+            let mut style = TextSpan::new("");
+            for (name, value) in tags {
+                apply_tag(&mut style, name, value);
+            }
+            style
+        };
+        let mut flush = |text: &mut String, tags: &[(&'a str, &'a str)]| {
+            if !text.is_empty() {
+                spans.push(TextSpan {
+                    text: Cow::Owned(std::mem::take(text)),
+                    ..style_of(tags)
+                });
+            }
+        };
+
+        while let Some(open) = rest.find('[') {
+            text.push_str(&rest[..open]);
+            rest = &rest[open..];
+
+            if let Some(after) = rest.strip_prefix("[[") {
+                text.push('[');
+                rest = after;
+                continue;
+            }
+            let Some(close) = rest.find(']') else {
+                break;
+            };
+            let tag = &rest[1..close];
+
+            if let Some(name) = tag.strip_prefix('/') {
+                if let Some(i) = open_tags.iter().rposition(|(open, _)| *open == name) {
+                    flush(&mut text, &open_tags);
+                    open_tags.remove(i);
+                    rest = &rest[close + 1..];
+                    continue;
+                }
+            } else {
+                let (name, value) = tag.split_once('=').unwrap_or((tag, ""));
+                if apply_tag(&mut TextSpan::new(""), name, value) {
+                    flush(&mut text, &open_tags);
+                    open_tags.push((name, value));
+                    rest = &rest[close + 1..];
+                    continue;
+                }
+            }
+
+            // not a tag, keep the bracket as text
+            text.push('[');
+            rest = &rest[1..];
+        }
+        text.push_str(rest);
+        flush(&mut text, &open_tags);
+
+        spans
+    }
+}
+
+/// applies a markup tag to a span's style, returns false for unknown tags or invalid values
+fn apply_tag<'a>(style: &mut TextSpan<'a>, name: &str, value: &'a str) -> bool {
+    match (name, value) {
+        ("b", "") => style.weight = Some(cosmic_text::Weight::BOLD),
+        ("i", "") => style.style = Some(cosmic_text::Style::Italic),
+        ("u", "") => style.underline = true,
+        ("s", "") => style.strikethrough = true,
+        ("color", v) => match Color::hex(v) {
+            Some(c) => style.color = Some(c),
+            None => return false,
+        },
+        ("bg", v) => match Color::hex(v) {
+            Some(c) => style.background = Some(c),
+            None => return false,
+        },
+        ("size", v) => match v.parse() {
+            Ok(size) => style.size = Some(size),
+            Err(_) => return false,
+        },
+        ("font", v) if !v.is_empty() => style.family = Some(cosmic_text::Family::Name(v)),
+        _ => return false,
+    }
+    true
+}
+
+#[must_use = "this command does nothing until you call `draw()`"]
+pub struct RichTextBuilder<'a, 'r> {
+    pub(crate) canvas: &'r mut Canvas<'a>,
+    pub(crate) spans: &'r [TextSpan<'r>],
+    pub(crate) x: f32,
+    pub(crate) y: f32,
+    pub(crate) w: Option<f32>,
+    pub(crate) h: Option<f32>,
+    pub(crate) size: f32,
+    pub(crate) line_height: f32,
+    pub(crate) family: Option<cosmic_text::Family<'a>>,
+    pub(crate) weight: cosmic_text::Weight,
+    pub(crate) style: cosmic_text::Style,
+    pub(crate) stretch: cosmic_text::Stretch,
+    pub(crate) align: cosmic_text::Align,
+}
+impl<'a, 'r> RichTextBuilder<'a, 'r> {
+    #[inline]
+    pub fn x(mut self, v: f32) -> Self {
+        self.x = v;
+        self
+    }
+    #[inline]
+    pub fn y(mut self, v: f32) -> Self {
+        self.y = v;
+        self
+    }
+    #[inline]
+    pub fn xy(mut self, x: f32, y: f32) -> Self {
+        self.x = x;
+        self.y = y;
+        self
+    }
+    #[inline]
+    pub fn w(mut self, v: f32) -> Self {
+        self.w = Some(v);
+        self
+    }
+    #[inline]
+    pub fn h(mut self, v: f32) -> Self {
+        self.h = Some(v);
+        self
+    }
+    #[inline]
+    pub fn wh(mut self, w: f32, h: f32) -> Self {
+        self.w = Some(w);
+        self.h = Some(h);
+        self
+    }
+    #[inline]
+    pub fn xywh(mut self, x: f32, y: f32, w: f32, h: f32) -> Self {
+        self.x = x;
+        self.y = y;
+        self.w = Some(w);
+        self.h = Some(h);
+        self
+    }
+    /// size of spans that don't set their own
+    #[inline]
+    pub fn size(mut self, v: f32) -> Self {
+        self.size = v;
+        self
+    }
+    #[inline]
+    pub fn line_height(mut self, v: f32) -> Self {
+        self.line_height = v;
+        self
+    }
+    /// family of spans that don't set their own
+    #[inline]
+    pub fn family(mut self, v: cosmic_text::Family<'a>) -> Self {
+        self.family = Some(v);
+        self
+    }
+    /// weight of spans that don't set their own
+    #[inline]
+    pub fn weight(mut self, v: cosmic_text::Weight) -> Self {
+        self.weight = v;
+        self
+    }
+    /// style of spans that don't set their own
+    #[inline]
+    pub fn style(mut self, v: cosmic_text::Style) -> Self {
+        self.style = v;
+        self
+    }
+    #[inline]
+    pub fn stretch(mut self, v: cosmic_text::Stretch) -> Self {
+        self.stretch = v;
+        self
+    }
+    #[inline]
+    pub fn align(mut self, v: cosmic_text::Align) -> Self {
+        self.align = v;
+        self
+    }
+    pub fn draw(mut self) {
+        let (text, spans) = self.shaping_spans();
+        let v = get_and_shape_buffer(
+            &mut self.canvas.ctx.inner.gpu_data.font_system,
+            &mut self.canvas.ctx.inner.buffer_cache,
+            &mut self.canvas.ctx.inner.current_stats,
+            self.w,
+            self.h,
+            &text,
+            self.size,
+            self.line_height,
+            self.family
+                .unwrap_or(default_family(&self.canvas.ctx.inner.default_font_family)),
+            self.weight,
+            self.style,
+            self.stretch,
+            self.align,
+            cosmic_text::Wrap::WordOrGlyph,
+            spans,
+        );
+
+        // the buffer is borrowed from the context, so collect what to draw before drawing it
+        let mut glyphs = vec![];
+        let mut backgrounds = vec![];
+        let mut lines = vec![];
+        for run in v.buffer.layout_runs() {
+            for group in run.glyphs.chunk_by(|a, b| a.metadata == b.metadata) {
+                let span = &self.spans[group[0].metadata];
+                let x0 = group.iter().map(|g| g.x).fold(f32::MAX, f32::min);
+                let x1 = group.iter().map(|g| g.x + g.w).fold(f32::MIN, f32::max);
+                let size = group.iter().map(|g| g.font_size).fold(0.0, f32::max);
+                let color = span.color.unwrap_or(self.canvas.fill_color);
+                let thickness = (size / 14.0).max(1.0);
+
+                if let Some(background) = span.background {
+                    backgrounds.push((
+                        vec2(x0, run.line_top),
+                        vec2(x1 - x0, run.line_height),
+                        background,
+                    ));
+                }
+                if span.underline {
+                    lines.push((
+                        vec2(x0, run.line_y + size * 0.1),
+                        vec2(x1 - x0, thickness),
+                        color,
+                    ));
+                }
+                if span.strikethrough {
+                    lines.push((
+                        vec2(x0, run.line_y - size * 0.3),
+                        vec2(x1 - x0, thickness),
+                        color,
+                    ));
+                }
+                glyphs.extend(
+                    group
+                        .iter()
+                        .map(|g| (g.physical((0.0, 0.0), 1.0), run.line_y, color)),
+                );
+            }
+        }
+
+        let offset = vec2(self.x, self.y);
+        for (pos, size, color) in backgrounds {
+            fill_quad(self.canvas, offset + pos, size, color);
+        }
+        for (physical, line_y, color) in glyphs {
+            self.canvas.draw_glyph(physical, line_y, color, offset);
+        }
+        for (pos, size, color) in lines {
+            fill_quad(self.canvas, offset + pos, size, color);
+        }
+    }
+    pub fn measure(&mut self) -> Vec2 {
+        let (text, spans) = self.shaping_spans();
+        let v = get_and_shape_buffer(
+            &mut self.canvas.ctx.inner.gpu_data.font_system,
+            &mut self.canvas.ctx.inner.buffer_cache,
+            &mut self.canvas.ctx.inner.current_stats,
+            self.w,
+            self.h,
+            &text,
+            self.size,
+            self.line_height,
+            self.family
+                .unwrap_or(default_family(&self.canvas.ctx.inner.default_font_family)),
+            self.weight,
+            self.style,
+            self.stretch,
+            self.align,
+            cosmic_text::Wrap::WordOrGlyph,
+            spans,
+        );
+
+        Vec2::from_array(text_buffer_dimensions(&v.buffer))
+    }
+
+    /// the text of all spans joined together and the attributes each byte range is shaped with,
+    /// every span's index is its glyphs' metadata
+    fn shaping_spans(&mut self) -> (String, Vec<(Range<usize>, cosmic_text::AttrsOwned)>) {
+        let font_system = &mut self.canvas.ctx.inner.gpu_data.font_system;
+        let family = self
+            .family
+            .unwrap_or(default_family(&self.canvas.ctx.inner.default_font_family));
+        let mut text = String::new();
+        let spans = self
+            .spans
+            .iter()
+            .enumerate()
+            .map(|(i, span)| {
+                let start = text.len();
+                text.push_str(&span.text);
+                let mut attrs = find_closest_attrs(
+                    font_system.db(),
+                    span.family.unwrap_or(family),
+                    span.weight.unwrap_or(self.weight),
+                    span.style.unwrap_or(self.style),
+                    self.stretch,
+                )
+                .metadata(i);
+                if let Some(size) = span.size {
+                    attrs = attrs.metrics(cosmic_text::Metrics::relative(size, self.line_height));
+                }
+                (start..text.len(), cosmic_text::AttrsOwned::new(&attrs))
+            })
+            .collect();
+        (text, spans)
+    }
+}
+
+/// a solid quad with the current transform, without touching the canvas' fill and stroke state
+fn fill_quad(canvas: &mut Canvas, pos: Vec2, size: Vec2, color: Color) {
+    let vertices = [
+        pos,
+        pos + vec2(size.x, 0.0),
+        pos + size,
+        pos + vec2(0.0, size.y),
+    ]
+    .map(|p| canvas.vertex(p, color, vec2(-1.0, 0.0), vec2(-1.0, 0.0)));
+    canvas.raw_indexed(vertices, QUAD_INDICES);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_text() {
+        assert_eq!(
+            TextSpan::parse_markup("hello"),
+            vec![TextSpan::new("hello")]
+        );
+        assert_eq!(TextSpan::parse_markup(""), vec![]);
+    }
+
+    #[test]
+    fn tags() {
+        assert_eq!(
+            TextSpan::parse_markup("a [b]bold[/b] [i]italic[/i] [u]u[/u][s]s[/s]"),
+            vec![
+                TextSpan::new("a "),
+                TextSpan::new("bold").bold(),
+                TextSpan::new(" "),
+                TextSpan::new("italic").italic(),
+                TextSpan::new(" "),
+                TextSpan::new("u").underline(),
+                TextSpan::new("s").strikethrough(),
+            ]
+        );
+    }
+
+    #[test]
+    fn tag_values() {
+        assert_eq!(
+            TextSpan::parse_markup(
+                "[color=#ff0000]r[/color][bg=00f]b[/bg][size=20]l[/size][font=Mono]m[/font]"
+            ),
+            vec![
+                TextSpan::new("r").color(Color::rgb8(255, 0, 0)),
+                TextSpan::new("b").background(Color::rgb8(0, 0, 255)),
+                TextSpan::new("l").size(20.0),
+                TextSpan::new("m").family(cosmic_text::Family::Name("Mono")),
+            ]
+        );
+    }
+
+    #[test]
+    fn nesting() {
+        assert_eq!(
+            TextSpan::parse_markup("[b]a[i]b[/i]c[/b]d"),
+            vec![
+                TextSpan::new("a").bold(),
+                TextSpan::new("b").bold().italic(),
+                TextSpan::new("c").bold(),
+                TextSpan::new("d"),
+            ]
+        );
+        // tags don't have to be closed in the order they were opened
+        assert_eq!(
+            TextSpan::parse_markup("[b]a[i]b[/b]c[/i]"),
+            vec![
+                TextSpan::new("a").bold(),
+                TextSpan::new("b").bold().italic(),
+                TextSpan::new("c").italic(),
+            ]
+        );
+        // closing a tag opened twice only closes the inner one
+        assert_eq!(
+            TextSpan::parse_markup("[size=10]a[size=20]b[/size]c[/size]"),
+            vec![
+                TextSpan::new("a").size(10.0),
+                TextSpan::new("b").size(20.0),
+                TextSpan::new("c").size(10.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn unclosed_tags_last_to_the_end() {
+        assert_eq!(
+            TextSpan::parse_markup("[b]a"),
+            vec![TextSpan::new("a").bold()]
+        );
+    }
+
+    #[test]
+    fn unmatched_close_is_text() {
+        assert_eq!(
+            TextSpan::parse_markup("a[/b]c"),
+            vec![TextSpan::new("a[/b]c")]
+        );
+        assert_eq!(
+            TextSpan::parse_markup("[i]a[/b][/i]"),
+            vec![TextSpan::new("a[/b]").italic()]
+        );
+    }
+
+    #[test]
+    fn escaped_bracket() {
+        assert_eq!(TextSpan::parse_markup("[[b]a"), vec![TextSpan::new("[b]a")]);
+        assert_eq!(
+            TextSpan::parse_markup("[b][[[/b]"),
+            vec![TextSpan::new("[").bold()]
+        );
+    }
+
+    #[test]
+    fn unterminated_bracket_is_text() {
+        assert_eq!(TextSpan::parse_markup("a [b"), vec![TextSpan::new("a [b")]);
+        assert_eq!(
+            TextSpan::parse_markup("[b]a [i"),
+            vec![TextSpan::new("a [i").bold()]
+        );
+    }
+
+    #[test]
+    fn unknown_or_invalid_tags_are_text() {
+        for markup in [
+            "[x]a",
+            "[b=1]a",
+            "[color=red]a",
+            "[color=#12345]a",
+            "[bg=]a",
+            "[size=big]a",
+            "[font=]a",
+            "[]a",
+        ] {
+            assert_eq!(
+                TextSpan::parse_markup(markup),
+                vec![TextSpan::new(markup)],
+                "{markup}"
+            );
+        }
+        // the invalid tag's closer has nothing to match either
+        assert_eq!(
+            TextSpan::parse_markup("[size=big]a[/size]"),
+            vec![TextSpan::new("[size=big]a[/size]")]
+        );
+    }
+}
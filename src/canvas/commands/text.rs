@@ -1,4 +1,4 @@
-use std::f32::consts::PI;
+use std::{f32::consts::PI, ops::Range};
 
 use ahash::AHashMap;
 use glam::{Vec2, vec2};
@@ -6,13 +6,24 @@ use itertools::Itertools;
 
 use crate::{
     Canvas, Color,
-    context::{BufferCacheKey, BufferCacheValue},
+    context::{BufferCacheKey, BufferCacheValue, fonts::default_family, stats::FrameStats},
     render::text::{
-        HashableAlign, HashableMetrics, find_closest_attrs, glyph::prepare_glyph,
+        HashableAlign, HashableMetrics, find_closest_attrs,
+        glyph::{GlyphQuad, clip_glyph, glyph_effect, prepare_glyph},
+        sdf::prepare_sdf_glyph,
         text_buffer_dimensions,
     },
 };
 
+/// where text sits vertically inside its box when it has a height
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum VerticalAlign {
+    #[default]
+    Top,
+    Center,
+    Bottom,
+}
+
 #[must_use = "this command does nothing until you call `draw()`"]
 pub struct TextBuilder<'a, 'r> {
     pub(crate) canvas: &'r mut Canvas<'a>,
@@ -23,11 +34,20 @@ pub struct TextBuilder<'a, 'r> {
     pub(crate) h: Option<f32>,
     pub(crate) size: f32,
     pub(crate) line_height: f32,
-    pub(crate) family: cosmic_text::Family<'a>,
+    /// the context's default family if none
+    pub(crate) family: Option<cosmic_text::Family<'a>>,
     pub(crate) weight: cosmic_text::Weight,
     pub(crate) style: cosmic_text::Style,
     pub(crate) stretch: cosmic_text::Stretch,
     pub(crate) align: cosmic_text::Align,
+    pub(crate) valign: VerticalAlign,
+    pub(crate) wrap: cosmic_text::Wrap,
+    pub(crate) ellipsis: bool,
+    pub(crate) clip: bool,
+    pub(crate) outline: Option<(f32, Color)>,
+    pub(crate) shadow: Option<(Vec2, f32, Color)>,
+    pub(crate) glow: Option<(f32, Color)>,
+    pub(crate) sdf: bool,
 }
 impl<'a, 'r> TextBuilder<'a, 'r> {
     #[inline]
@@ -82,7 +102,7 @@ impl<'a, 'r> TextBuilder<'a, 'r> {
     }
     #[inline]
     pub fn family(mut self, v: cosmic_text::Family<'a>) -> Self {
-        self.family = v;
+        self.family = Some(v);
         self
     }
     #[inline]
@@ -105,93 +125,398 @@ impl<'a, 'r> TextBuilder<'a, 'r> {
         self.align = v;
         self
     }
-    pub fn draw(self) {
+    /// only does something with a height set
+    #[inline]
+    pub fn valign(mut self, v: VerticalAlign) -> Self {
+        self.valign = v;
+        self
+    }
+    /// how lines break when they're wider than `w`, `WordOrGlyph` by default
+    #[inline]
+    pub fn wrap(mut self, v: cosmic_text::Wrap) -> Self {
+        self.wrap = v;
+        self
+    }
+    /// ends the last line with "…" when the text doesn't fit in the box.
+    /// lines that would only be partly inside the height are dropped
+    #[inline]
+    pub fn ellipsis(mut self, v: bool) -> Self {
+        self.ellipsis = v;
+        self
+    }
+    /// cuts off anything outside the box, glyphs on the edge are drawn partially
+    #[inline]
+    pub fn clip(mut self, v: bool) -> Self {
+        self.clip = v;
+        self
+    }
+    /// draws a border of `width` pixels around every glyph. outlines, shadows and glows reach at
+    /// most 3 pixels unless the text is drawn with `sdf`
+    #[inline]
+    pub fn outline(mut self, width: f32, color: Color) -> Self {
+        self.outline = Some((width, color));
+        self
+    }
+    /// draws a copy of the text moved by `offset` behind it, with edges softened over `blur` pixels
+    #[inline]
+    pub fn shadow(mut self, offset: Vec2, blur: f32, color: Color) -> Self {
+        self.shadow = Some((offset, blur, color));
+        self
+    }
+    /// draws the glyphs from distance fields so they stay sharp when the text is scaled, rotated or
+    /// very large. they're a bit softer than normal glyphs at small sizes, and outlines, shadows
+    /// and glows are cheaper but limited to a few pixels at `SDF_SIZE`
+    #[inline]
+    pub fn sdf(mut self, v: bool) -> Self {
+        self.sdf = v;
+        self
+    }
+    /// surrounds the text with a soft halo fading out over `radius` pixels
+    #[inline]
+    pub fn glow(mut self, radius: f32, color: Color) -> Self {
+        self.glow = Some((radius, color));
+        self
+    }
+    pub fn draw(mut self) {
+        let ellipsis = if self.ellipsis {
+            self.ellipsis_glyphs()
+        } else {
+            vec![]
+        };
+
         let v = get_and_shape_buffer(
             &mut self.canvas.ctx.inner.gpu_data.font_system,
             &mut self.canvas.ctx.inner.buffer_cache,
+            &mut self.canvas.ctx.inner.current_stats,
             self.w,
             self.h,
             self.text,
             self.size,
             self.line_height,
-            self.family,
+            self.family
+                .unwrap_or(default_family(&self.canvas.ctx.inner.default_font_family)),
             self.weight,
             self.style,
             self.stretch,
             self.align,
+            self.wrap,
+            vec![],
         );
 
-        for run in v.buffer.layout_runs() {
-            for glyph in run.glyphs {
-                let physical = glyph.physical((0.0, 0.0), 1.0);
-
-                if let Some([mut a, mut b, mut c, mut d]) = prepare_glyph(
-                    physical,
-                    run.line_y,
-                    &mut self.canvas.ctx.inner.gpu_data,
-                    self.canvas.fill_color.to_array(),
-                    self.x,
-                    self.y,
-                ) {
-                    a.pos = self
-                        .canvas
-                        .transform
-                        .transform_point2(Vec2::from_array(a.pos))
-                        .to_array();
-                    b.pos = self
-                        .canvas
-                        .transform
-                        .transform_point2(Vec2::from_array(b.pos))
-                        .to_array();
-                    c.pos = self
-                        .canvas
-                        .transform
-                        .transform_point2(Vec2::from_array(c.pos))
-                        .to_array();
-                    d.pos = self
-                        .canvas
-                        .transform
-                        .transform_point2(Vec2::from_array(d.pos))
-                        .to_array();
-                    self.canvas.ctx.inner.vertices.extend([a, b, c, a, c, d]);
+        let mut runs = v.buffer.layout_runs().collect::<Vec<_>>();
+        // index of the run that gets cut short and where the ellipsis starts on it
+        let mut truncated = None;
+        if !ellipsis.is_empty() {
+            let all_runs = runs.len();
+            if let Some(h) = self.h {
+                runs.retain(|run| run.line_top + run.line_height <= h + 0.5);
+            }
+            let ellipsis_w = ellipsis.iter().map(|g| g.w).sum::<f32>();
+            if let Some(last) = runs.last() {
+                let max_w = self.w.unwrap_or(f32::INFINITY);
+                if runs.len() < all_runs || !ends_buffer(&v.buffer, last) || last.line_w > max_w {
+                    let limit = self.w.map_or(f32::INFINITY, |w| w - ellipsis_w);
+                    let end = last
+                        .glyphs
+                        .iter()
+                        .take_while(|g| g.x + g.w <= limit)
+                        .last()
+                        .map_or(0.0, |g| g.x + g.w);
+                    truncated = Some((runs.len() - 1, end.min(limit.max(0.0))));
                 }
             }
         }
+
+        let offset = vec2(
+            self.x,
+            self.y + valign_offset(self.valign, self.h, runs.last()),
+        );
+        let clip = self.clip.then(|| {
+            (
+                vec2(self.x, self.y),
+                vec2(
+                    self.x + self.w.unwrap_or(f32::INFINITY),
+                    self.y + self.h.unwrap_or(f32::INFINITY),
+                ),
+            )
+        });
+
+        let mut quads = vec![];
+        for (i, run) in runs.iter().enumerate() {
+            let (glyphs, ellipsis_x) = match truncated {
+                Some((line, x)) if line == i => (
+                    &run.glyphs[..run.glyphs.iter().take_while(|g| g.x + g.w <= x).count()],
+                    Some(x),
+                ),
+                _ => (run.glyphs, None),
+            };
+            let ellipsis = ellipsis_x
+                .into_iter()
+                .flat_map(|x| ellipsis.iter().map(move |g| (g, x)));
+
+            for (glyph, x) in glyphs.iter().map(|g| (g, 0.0)).chain(ellipsis) {
+                let gpu_data = &mut self.canvas.ctx.inner.gpu_data;
+                let color = self.canvas.fill_color.to_array();
+                let sdf_quad = if self.sdf {
+                    prepare_sdf_glyph(glyph, run.line_y, gpu_data, color, offset.x + x, offset.y)
+                } else {
+                    None
+                };
+                // color glyphs aren't in the sdf atlas and are drawn as usual
+                quads.extend(sdf_quad.or_else(|| {
+                    prepare_glyph(
+                        glyph.physical((x, 0.0), 1.0),
+                        run.line_y,
+                        gpu_data,
+                        color,
+                        offset.x,
+                        offset.y,
+                    )
+                    .map(GlyphQuad::from_vertices)
+                }));
+            }
+        }
+
+        // every effect goes under all of the text so it doesn't cover neighbouring glyphs
+        let outline = self.outline.map_or(0.0, |(width, _)| width);
+        let mut layers = vec![];
+        if let Some((shadow_offset, blur, color)) = self.shadow {
+            layers.extend(
+                quads.iter().map(|&quad| {
+                    glyph_effect(quad, outline, blur, shadow_offset, color.to_array())
+                }),
+            );
+        }
+        if let Some((radius, color)) = self.glow {
+            layers.extend(
+                quads
+                    .iter()
+                    .map(|&quad| glyph_effect(quad, outline, radius, Vec2::ZERO, color.to_array())),
+            );
+        }
+        if let Some((width, color)) = self.outline {
+            layers.extend(
+                quads
+                    .iter()
+                    .map(|&quad| glyph_effect(quad, width, 0.0, Vec2::ZERO, color.to_array())),
+            );
+        }
+        layers.extend(quads);
+
+        let transform = self.canvas.transform;
+        self.canvas.glyph_quads(
+            layers
+                .into_iter()
+                .filter_map(|quad| match clip {
+                    Some((min, max)) => clip_glyph(quad, min, max),
+                    None => Some(quad),
+                })
+                .map(|quad| (quad, transform)),
+        );
     }
     pub fn measure(&mut self) -> Vec2 {
         let v = get_and_shape_buffer(
             &mut self.canvas.ctx.inner.gpu_data.font_system,
             &mut self.canvas.ctx.inner.buffer_cache,
+            &mut self.canvas.ctx.inner.current_stats,
             self.w,
             self.h,
             self.text,
             self.size,
             self.line_height,
-            self.family,
+            self.family
+                .unwrap_or(default_family(&self.canvas.ctx.inner.default_font_family)),
             self.weight,
             self.style,
             self.stretch,
             self.align,
+            self.wrap,
+            vec![],
         );
 
         Vec2::from_array(text_buffer_dimensions(&v.buffer))
     }
+
+    /// the text position under `point`, in the same coordinates as `x`/`y` (before `canvas.transform`).
+    /// returns none if the point isn't over a line's glyphs
+    pub fn hit_test(&mut self, point: Vec2) -> Option<cosmic_text::Cursor> {
+        let (x, y, valign, h) = (self.x, self.y, self.valign, self.h);
+        let buffer = self.shaped_buffer();
+        let y = y + valign_offset(valign, h, buffer.layout_runs().last().as_ref());
+        let local = point - vec2(x, y);
+        let run = buffer
+            .layout_runs()
+            .find(|run| (run.line_top..run.line_top + run.line_height).contains(&local.y))?;
+        let (first, last) = (run.glyphs.first()?, run.glyphs.last()?);
+        let (left, right) = (
+            first.x.min(last.x),
+            (first.x + first.w).max(last.x + last.w),
+        );
+        if !(left..right).contains(&local.x) {
+            return None;
+        }
+        buffer.hit(local.x, local.y)
+    }
+    /// the (position, size) of a caret placed at `cursor`, one line tall and zero wide
+    pub fn caret_rect(&mut self, cursor: cosmic_text::Cursor) -> Option<(Vec2, Vec2)> {
+        let (x, y, valign, h) = (self.x, self.y, self.valign, self.h);
+        let buffer = self.shaped_buffer();
+        let y = y + valign_offset(valign, h, buffer.layout_runs().last().as_ref());
+        buffer.layout_runs().find_map(|run| {
+            let caret_x = cursor_x(&cursor, &run)?;
+            Some((
+                vec2(x + caret_x, y + run.line_top),
+                vec2(0.0, run.line_height),
+            ))
+        })
+    }
+    /// the (position, size) of a rect per layout line covered by the selection between the two cursors
+    pub fn selection_rects(&mut self, range: Range<cosmic_text::Cursor>) -> Vec<(Vec2, Vec2)> {
+        let (x, y, valign, h) = (self.x, self.y, self.valign, self.h);
+        let (start, end) = if range.start <= range.end {
+            (range.start, range.end)
+        } else {
+            (range.end, range.start)
+        };
+        let buffer = self.shaped_buffer();
+        let y = y + valign_offset(valign, h, buffer.layout_runs().last().as_ref());
+        buffer
+            .layout_runs()
+            .filter(|run| (start.line..=end.line).contains(&run.line_i))
+            .filter_map(|run| {
+                let (sel_x, sel_w) = run.highlight(start, end)?;
+                Some((
+                    vec2(x + sel_x, y + run.line_top),
+                    vec2(sel_w, run.line_height),
+                ))
+            })
+            .collect()
+    }
+
+    fn shaped_buffer(&mut self) -> &mut cosmic_text::Buffer {
+        &mut get_and_shape_buffer(
+            &mut self.canvas.ctx.inner.gpu_data.font_system,
+            &mut self.canvas.ctx.inner.buffer_cache,
+            &mut self.canvas.ctx.inner.current_stats,
+            self.w,
+            self.h,
+            self.text,
+            self.size,
+            self.line_height,
+            self.family
+                .unwrap_or(default_family(&self.canvas.ctx.inner.default_font_family)),
+            self.weight,
+            self.style,
+            self.stretch,
+            self.align,
+            self.wrap,
+            vec![],
+        )
+        .buffer
+    }
+    /// "…" shaped with the same font and size as the text
+    fn ellipsis_glyphs(&mut self) -> Vec<cosmic_text::LayoutGlyph> {
+        let v = get_and_shape_buffer(
+            &mut self.canvas.ctx.inner.gpu_data.font_system,
+            &mut self.canvas.ctx.inner.buffer_cache,
+            &mut self.canvas.ctx.inner.current_stats,
+            None,
+            None,
+            "…",
+            self.size,
+            self.line_height,
+            self.family
+                .unwrap_or(default_family(&self.canvas.ctx.inner.default_font_family)),
+            self.weight,
+            self.style,
+            self.stretch,
+            cosmic_text::Align::Left,
+            cosmic_text::Wrap::None,
+            vec![],
+        );
+        v.buffer
+            .layout_runs()
+            .flat_map(|run| run.glyphs.iter().cloned())
+            .collect()
+    }
+}
+
+/// how far down the text is moved to align the runs up to `last` inside the height
+fn valign_offset(
+    valign: VerticalAlign,
+    h: Option<f32>,
+    last: Option<&cosmic_text::LayoutRun>,
+) -> f32 {
+    let (Some(h), Some(last)) = (h, last) else {
+        return 0.0;
+    };
+    let free = (h - (last.line_top + last.line_height)).max(0.0);
+    match valign {
+        VerticalAlign::Top => 0.0,
+        VerticalAlign::Center => free / 2.0,
+        VerticalAlign::Bottom => free,
+    }
+}
+
+/// whether `run` is the last layout line of the buffer, lines after the visible ones aren't laid out
+fn ends_buffer(buffer: &cosmic_text::Buffer, run: &cosmic_text::LayoutRun) -> bool {
+    run.line_i + 1 == buffer.lines.len()
+        && buffer.lines[run.line_i]
+            .layout_opt()
+            .and_then(|layout| layout.last())
+            .is_some_and(|line| std::ptr::eq(line.glyphs.as_slice(), run.glyphs))
+}
+
+/// x offset of a cursor within a layout run, or none if the cursor isn't on that run
+fn cursor_x(cursor: &cosmic_text::Cursor, run: &cosmic_text::LayoutRun) -> Option<f32> {
+    if cursor.line != run.line_i {
+        return None;
+    }
+    let Some(last) = run.glyphs.last() else {
+        // empty lines have a single run and the caret sits at its start
+        return Some(0.0);
+    };
+    if let Some(glyph) = run
+        .glyphs
+        .iter()
+        .find(|g| (g.start..g.end).contains(&cursor.index))
+    {
+        // cursors inside a ligature are placed proportionally through it
+        let cluster = &run.text[glyph.start..glyph.end];
+        let before = run.text[glyph.start..cursor.index].chars().count() as f32;
+        let offset = glyph.w * before / cluster.chars().count().max(1) as f32;
+        return Some(if glyph.level.is_rtl() {
+            glyph.x + glyph.w - offset
+        } else {
+            glyph.x + offset
+        });
+    }
+    (cursor.index == last.end).then(|| {
+        if last.level.is_rtl() {
+            last.x
+        } else {
+            last.x + last.w
+        }
+    })
 }
 
 // no view types so gotta do this
 pub fn get_and_shape_buffer<'a>(
     font_system: &mut cosmic_text::FontSystem,
     buffer_cache: &'a mut AHashMap<BufferCacheKey, BufferCacheValue>,
+    stats: &mut FrameStats,
     w: Option<f32>,
     h: Option<f32>,
     text: &'a str,
     size: f32,
     line_height: f32,
-    family: cosmic_text::Family<'a>,
+    family: cosmic_text::Family<'_>,
     weight: cosmic_text::Weight,
     style: cosmic_text::Style,
     stretch: cosmic_text::Stretch,
     align: cosmic_text::Align,
+    wrap: cosmic_text::Wrap,
+    spans: Vec<(Range<usize>, cosmic_text::AttrsOwned)>,
 ) -> &'a mut BufferCacheValue {
     let metrics = cosmic_text::Metrics::relative(size, line_height);
     let attrs = cosmic_text::AttrsOwned::new(&find_closest_attrs(
@@ -208,17 +533,32 @@ pub fn get_and_shape_buffer<'a>(
             attrs: attrs.clone(),
             align: HashableAlign(align),
             text: text.into(),
+            spans,
         })
-        .or_insert_with(|| {
+        .and_modify(|_| stats.text_cache_hits += 1)
+        .or_insert_with_key(|key| {
+            stats.text_cache_misses += 1;
             let mut buffer = cosmic_text::Buffer::new(font_system, metrics);
 
-            buffer.set_rich_text(
-                font_system,
-                [(text, attrs.as_attrs())],
-                &attrs.as_attrs(),
-                cosmic_text::Shaping::Advanced,
-                Some(align),
-            );
+            if key.spans.is_empty() {
+                buffer.set_rich_text(
+                    font_system,
+                    [(text, attrs.as_attrs())],
+                    &attrs.as_attrs(),
+                    cosmic_text::Shaping::Advanced,
+                    Some(align),
+                );
+            } else {
+                buffer.set_rich_text(
+                    font_system,
+                    key.spans
+                        .iter()
+                        .map(|(range, attrs)| (&text[range.clone()], attrs.as_attrs())),
+                    &attrs.as_attrs(),
+                    cosmic_text::Shaping::Advanced,
+                    Some(align),
+                );
+            }
 
             BufferCacheValue {
                 buffer,
@@ -227,6 +567,7 @@ pub fn get_and_shape_buffer<'a>(
         });
     v.in_use = true;
 
+    v.buffer.set_wrap(font_system, wrap);
     v.buffer.set_size(font_system, w, h);
     v.buffer.shape_until_scroll(font_system, true);
 
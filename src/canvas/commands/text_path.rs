@@ -0,0 +1,252 @@
+use glam::{Affine2, Vec2, vec2};
+
+use crate::{
+    Canvas,
+    canvas::commands::text::get_and_shape_buffer,
+    context::fonts::default_family,
+    render::text::{
+        glyph::{GlyphQuad, prepare_glyph},
+        sdf::prepare_sdf_glyph,
+    },
+};
+
+/// a curve for `Canvas::text_on_path` to lay text along
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TextPath<'p> {
+    /// straight segments between the points, curves can be flattened into these
+    Polyline(&'p [Vec2]),
+    /// a circle running clockwise from `start_angle` (in radians, 0 is to the right) with the text
+    /// facing outwards. a negative radius runs counter-clockwise with the text facing the center
+    Arc {
+        center: Vec2,
+        radius: f32,
+        start_angle: f32,
+    },
+}
+
+impl TextPath<'_> {
+    /// length of the path, infinite for arcs since the text can wrap around more than once
+    pub fn length(&self) -> f32 {
+        match self {
+            Self::Polyline(points) => points.windows(2).map(|p| p[0].distance(p[1])).sum(),
+            Self::Arc { .. } => f32::INFINITY,
+        }
+    }
+
+    /// the point `distance` along the path and the direction it's heading in there.
+    /// polylines continue in a straight line before their start and after their end
+    pub fn point_at(&self, distance: f32) -> (Vec2, Vec2) {
+        match *self {
+            Self::Polyline(points) => {
+                let segments = points.windows(2).filter(|p| p[0] != p[1]);
+                let mut last = None;
+                let mut start = 0.0;
+                for (i, p) in segments.enumerate() {
+                    let length = p[0].distance(p[1]);
+                    let dir = (p[1] - p[0]) / length;
+                    // the first segment also covers distances before the start of the path
+                    if distance < start + length || (i == 0 && distance < start) {
+                        return (p[0] + dir * (distance - start), dir);
+                    }
+                    last = Some((p[0], dir, start));
+                    start += length;
+                }
+                match last {
+                    Some((from, dir, start)) => (from + dir * (distance - start), dir),
+                    None => (points.first().copied().unwrap_or(Vec2::ZERO), Vec2::X),
+                }
+            }
+            Self::Arc {
+                center,
+                radius,
+                start_angle,
+            } => {
+                let radial = Vec2::from_angle(start_angle + distance / radius);
+                (
+                    center + radial * radius.abs(),
+                    radial.perp() * radius.signum(),
+                )
+            }
+        }
+    }
+}
+
+#[must_use = "this command does nothing until you call `draw()`"]
+pub struct TextOnPathBuilder<'a, 'r> {
+    pub(crate) canvas: &'r mut Canvas<'a>,
+    pub(crate) text: &'r str,
+    pub(crate) path: TextPath<'r>,
+    pub(crate) offset: f32,
+    pub(crate) baseline: f32,
+    pub(crate) size: f32,
+    pub(crate) line_height: f32,
+    pub(crate) family: Option<cosmic_text::Family<'a>>,
+    pub(crate) weight: cosmic_text::Weight,
+    pub(crate) style: cosmic_text::Style,
+    pub(crate) stretch: cosmic_text::Stretch,
+    pub(crate) align: cosmic_text::Align,
+    pub(crate) sdf: bool,
+}
+impl<'a, 'r> TextOnPathBuilder<'a, 'r> {
+    /// moves the text along the path by this distance
+    #[inline]
+    pub fn offset(mut self, v: f32) -> Self {
+        self.offset = v;
+        self
+    }
+    /// moves the text's baseline away from the path, positive is towards the glyphs' tops
+    #[inline]
+    pub fn baseline(mut self, v: f32) -> Self {
+        self.baseline = v;
+        self
+    }
+    #[inline]
+    pub fn size(mut self, v: f32) -> Self {
+        self.size = v;
+        self
+    }
+    #[inline]
+    pub fn line_height(mut self, v: f32) -> Self {
+        self.line_height = v;
+        self
+    }
+    #[inline]
+    pub fn family(mut self, v: cosmic_text::Family<'a>) -> Self {
+        self.family = Some(v);
+        self
+    }
+    #[inline]
+    pub fn weight(mut self, v: cosmic_text::Weight) -> Self {
+        self.weight = v;
+        self
+    }
+    #[inline]
+    pub fn style(mut self, v: cosmic_text::Style) -> Self {
+        self.style = v;
+        self
+    }
+    #[inline]
+    pub fn stretch(mut self, v: cosmic_text::Stretch) -> Self {
+        self.stretch = v;
+        self
+    }
+    /// where the text sits on a polyline, `Center` and `Right` are relative to its length.
+    /// arcs are centered on their start angle
+    #[inline]
+    pub fn align(mut self, v: cosmic_text::Align) -> Self {
+        self.align = v;
+        self
+    }
+    /// see `TextBuilder::sdf`, keeps rotated glyphs smooth
+    #[inline]
+    pub fn sdf(mut self, v: bool) -> Self {
+        self.sdf = v;
+        self
+    }
+    pub fn draw(self) {
+        let v = get_and_shape_buffer(
+            &mut self.canvas.ctx.inner.gpu_data.font_system,
+            &mut self.canvas.ctx.inner.buffer_cache,
+            &mut self.canvas.ctx.inner.current_stats,
+            None,
+            None,
+            self.text,
+            self.size,
+            self.line_height,
+            self.family
+                .unwrap_or(default_family(&self.canvas.ctx.inner.default_font_family)),
+            self.weight,
+            self.style,
+            self.stretch,
+            cosmic_text::Align::Left,
+            cosmic_text::Wrap::None,
+            vec![],
+        );
+
+        let Some(first_line_y) = v.buffer.layout_runs().next().map(|run| run.line_y) else {
+            return;
+        };
+        let width = v
+            .buffer
+            .layout_runs()
+            .map(|run| run.line_w)
+            .fold(0.0, f32::max);
+        let length = self.path.length();
+        let start = self.offset
+            + match (self.align, self.path) {
+                (cosmic_text::Align::Center, TextPath::Arc { .. }) => -width / 2.0,
+                (cosmic_text::Align::Center, _) => (length - width) / 2.0,
+                (cosmic_text::Align::Right | cosmic_text::Align::End, TextPath::Arc { .. }) => {
+                    -width
+                }
+                (cosmic_text::Align::Right | cosmic_text::Align::End, _) => length - width,
+                _ => 0.0,
+            };
+
+        let mut quads = vec![];
+        for run in v.buffer.layout_runs() {
+            // later lines are stacked below the first one, away from the glyphs' tops
+            let line_offset = run.line_y - first_line_y - self.baseline;
+            for glyph in run.glyphs {
+                let middle = glyph.x + glyph.w / 2.0;
+                let (point, dir) = self.path.point_at(start + middle);
+                let placement = self.canvas.transform
+                    * Affine2::from_angle_translation(
+                        dir.to_angle(),
+                        point + dir.perp() * line_offset,
+                    );
+
+                let gpu_data = &mut self.canvas.ctx.inner.gpu_data;
+                let color = self.canvas.fill_color.to_array();
+                // every glyph is prepared around its own baseline center and then placed on the path
+                let sdf_quad = if self.sdf {
+                    prepare_sdf_glyph(glyph, 0.0, gpu_data, color, -middle, 0.0)
+                } else {
+                    None
+                };
+                let Some(quad) = sdf_quad.or_else(|| {
+                    prepare_glyph(
+                        glyph.physical((-middle, 0.0), 1.0),
+                        0.0,
+                        gpu_data,
+                        color,
+                        0.0,
+                        0.0,
+                    )
+                    .map(GlyphQuad::from_vertices)
+                }) else {
+                    continue;
+                };
+
+                quads.push((quad, placement));
+            }
+        }
+
+        self.canvas.glyph_quads(quads);
+    }
+    /// the size of the text before it's bent along the path
+    pub fn measure(&mut self) -> Vec2 {
+        let v = get_and_shape_buffer(
+            &mut self.canvas.ctx.inner.gpu_data.font_system,
+            &mut self.canvas.ctx.inner.buffer_cache,
+            &mut self.canvas.ctx.inner.current_stats,
+            None,
+            None,
+            self.text,
+            self.size,
+            self.line_height,
+            self.family
+                .unwrap_or(default_family(&self.canvas.ctx.inner.default_font_family)),
+            self.weight,
+            self.style,
+            self.stretch,
+            cosmic_text::Align::Left,
+            cosmic_text::Wrap::None,
+            vec![],
+        );
+        let (width, height) = v.buffer.layout_runs().fold((0.0, 0.0), |(w, _), run| {
+            (run.line_w.max(w), run.line_top + run.line_height)
+        });
+        vec2(width, height)
+    }
+}
@@ -3,7 +3,7 @@ use std::f32::consts::PI;
 use glam::{Vec2, vec2};
 use itertools::Itertools;
 
-use crate::{Canvas, Color};
+use crate::{Canvas, Color, canvas::QUAD_INDICES};
 
 #[must_use = "this command does nothing until you call `draw()`"]
 pub struct TextureBuilder<'a, 'r> {
@@ -120,33 +120,8 @@ impl<'a, 'r> TextureBuilder<'a, 'r> {
             Color::rgb(1.0, 1.0, 1.0)
         };
 
-        self.canvas.raw_tri(
-            a,
-            b,
-            c,
-            color,
-            color,
-            color,
-            uv_a,
-            uv_b,
-            uv_c,
-            vec2(-1.0, 0.0),
-            vec2(-1.0, 0.0),
-            vec2(-1.0, 0.0),
-        );
-        self.canvas.raw_tri(
-            a,
-            c,
-            d,
-            color,
-            color,
-            color,
-            uv_a,
-            uv_c,
-            uv_d,
-            vec2(-1.0, 0.0),
-            vec2(-1.0, 0.0),
-            vec2(-1.0, 0.0),
-        );
+        let vertices = [(a, uv_a), (b, uv_b), (c, uv_c), (d, uv_d)]
+            .map(|(p, uv)| self.canvas.vertex(p, color, uv, vec2(-1.0, 0.0)));
+        self.canvas.raw_indexed(vertices, QUAD_INDICES);
     }
 }
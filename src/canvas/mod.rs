@@ -10,28 +10,48 @@ use crate::{
     canvas::{
         color::Color,
         commands::{
-            ellipse::EllipseBuilder, rect::RectBuilder, text::TextBuilder, texture::TextureBuilder,
+            ellipse::EllipseBuilder,
+            mesh::MeshBuilder,
+            rect::RectBuilder,
+            rich_text::{RichTextBuilder, TextSpan},
+            text::{TextBuilder, VerticalAlign},
+            text_path::{TextOnPathBuilder, TextPath},
+            texture::TextureBuilder,
             triangle::TriangleBuilder,
         },
     },
-    context::{BlendMode, CanvasContext, Context, DrawCall, DrawCallType, texture::TextureKey},
-    render::shaders::wgsl_common,
+    context::{
+        BlendMode, CanvasContext, Context, DrawCall, DrawCallType, RenderPass, mesh::MeshKey,
+        texture::TextureKey,
+    },
+    render::{
+        shaders::{wgsl_common, wgsl_mesh},
+        text::glyph::GlyphQuad,
+    },
 };
 
+pub mod camera;
 pub mod color;
 pub mod commands;
+pub mod sprite;
+pub mod text_input;
 
 new_key_type! {
     pub struct CanvasKey;
 }
 
+/// two triangles making up a quad from 4 vertices in clockwise or counter-clockwise order
+pub(crate) const QUAD_INDICES: [u32; 6] = [0, 1, 2, 0, 2, 3];
+
 pub struct Canvas<'a> {
     pub(crate) key: CanvasKey,
     pub(crate) ctx: CanvasContext<'a>,
 
     pub(crate) current_texture: Option<TextureKey>,
     pub(crate) current_blend_mode: BlendMode,
+    pub(crate) current_layer: i32,
     pub(crate) stencil_reference: u32,
+    pub(crate) state_stack: Vec<CanvasState>,
 
     pub fill_color: Color,
     pub stroke_color: Color,
@@ -44,6 +64,21 @@ pub struct Canvas<'a> {
 
     pub transform: Affine2,
 }
+/// everything saved by `Canvas::push` and restored by `Canvas::pop`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct CanvasState {
+    texture: Option<TextureKey>,
+    blend_mode: BlendMode,
+    layer: i32,
+    fill_color: Color,
+    stroke_color: Color,
+    stroke_weight: f32,
+    draw_fill: bool,
+    draw_stroke: bool,
+    arc_segments: u16,
+    transform: Affine2,
+}
+
 impl<'a> Canvas<'a> {
     pub(crate) fn new(key: CanvasKey, ctx: CanvasContext<'a>) -> Self {
         Self {
@@ -51,7 +86,9 @@ impl<'a> Canvas<'a> {
             ctx,
             current_texture: None,
             current_blend_mode: BlendMode::Normal,
+            current_layer: 0,
             stencil_reference: 0,
+            state_stack: vec![],
             fill_color: Color::rgb(0.25, 0.25, 0.25),
             stroke_color: Color::rgb(0.75, 0.75, 0.75),
             stroke_weight: 2.0,
@@ -68,44 +105,43 @@ impl<'a> Canvas<'a> {
         self.key
     }
 
+    /// starts a new draw call with the current texture, blend mode, layer and clip state
+    pub(crate) fn push_draw_call(&mut self) {
+        self.ctx
+            .inner
+            .passes
+            .last_mut()
+            .unwrap()
+            .calls
+            .push(DrawCall {
+                start_index: self.ctx.inner.indices.len() as u32,
+                typ: DrawCallType::Draw {
+                    blend_mode: self.current_blend_mode,
+                    texture: self.current_texture,
+                    reference: self.stencil_reference,
+                    end_clip_reference: None,
+                    layer: self.current_layer,
+                },
+            });
+    }
     pub fn set_texture(&mut self, tex: TextureKey) {
         if Some(tex) != self.current_texture() {
-            self.ctx
-                .inner
-                .passes
-                .last_mut()
-                .unwrap()
-                .calls
-                .push(DrawCall {
-                    start_vertex: self.ctx.inner.vertices.len() as u32,
-                    typ: DrawCallType::Draw {
-                        blend_mode: self.current_blend_mode,
-                        set_texture: Some(tex),
-                        reference: self.stencil_reference,
-                        end_clip_reference: None,
-                    },
-                });
             self.current_texture = Some(tex);
+            self.push_draw_call();
         }
     }
     pub fn set_blend_mode(&mut self, mode: BlendMode) {
         if mode != self.current_blend_mode() {
-            self.ctx
-                .inner
-                .passes
-                .last_mut()
-                .unwrap()
-                .calls
-                .push(DrawCall {
-                    start_vertex: self.ctx.inner.vertices.len() as u32,
-                    typ: DrawCallType::Draw {
-                        blend_mode: mode,
-                        set_texture: None,
-                        reference: self.stencil_reference,
-                        end_clip_reference: None,
-                    },
-                });
             self.current_blend_mode = mode;
+            self.push_draw_call();
+        }
+    }
+    /// draws on higher layers end up above lower ones regardless of the order they were made in,
+    /// draws on the same layer keep their order
+    pub fn set_layer(&mut self, layer: i32) {
+        if layer != self.current_layer() {
+            self.current_layer = layer;
+            self.push_draw_call();
         }
     }
     pub fn current_texture(&mut self) -> Option<TextureKey> {
@@ -114,6 +150,56 @@ impl<'a> Canvas<'a> {
     pub fn current_blend_mode(&mut self) -> BlendMode {
         self.current_blend_mode
     }
+    pub fn current_layer(&mut self) -> i32 {
+        self.current_layer
+    }
+
+    /// saves the current drawing state (colors, stroke, transform, texture, blend mode and layer)
+    pub fn push(&mut self) {
+        self.state_stack.push(CanvasState {
+            texture: self.current_texture,
+            blend_mode: self.current_blend_mode,
+            layer: self.current_layer,
+            fill_color: self.fill_color,
+            stroke_color: self.stroke_color,
+            stroke_weight: self.stroke_weight,
+            draw_fill: self.draw_fill,
+            draw_stroke: self.draw_stroke,
+            arc_segments: self.arc_segments,
+            transform: self.transform,
+        });
+    }
+    /// restores the state saved by the last `push`, does nothing if there is none
+    pub fn pop(&mut self) {
+        let Some(state) = self.state_stack.pop() else {
+            return;
+        };
+        if (state.texture, state.blend_mode, state.layer)
+            != (
+                self.current_texture,
+                self.current_blend_mode,
+                self.current_layer,
+            )
+        {
+            self.current_texture = state.texture;
+            self.current_blend_mode = state.blend_mode;
+            self.current_layer = state.layer;
+            self.push_draw_call();
+        }
+        self.fill_color = state.fill_color;
+        self.stroke_color = state.stroke_color;
+        self.stroke_weight = state.stroke_weight;
+        self.draw_fill = state.draw_fill;
+        self.draw_stroke = state.draw_stroke;
+        self.arc_segments = state.arc_segments;
+        self.transform = state.transform;
+    }
+    pub fn with_state<R, F: FnOnce(&mut Canvas) -> R>(&mut self, cb: F) -> R {
+        self.push();
+        let r = cb(self);
+        self.pop();
+        r
+    }
 
     pub fn set_transform(&mut self, transform: Affine2) {
         self.transform = transform;
@@ -158,26 +244,79 @@ impl<'a> Canvas<'a> {
         text_uv_b: Vec2,
         text_uv_c: Vec2,
     ) {
-        self.ctx.inner.vertices.extend([
-            wgsl_common::structs::VertexInput::new(
-                self.transform.transform_point2(a).to_array(),
-                color_a.to_array(),
-                uv_a.to_array(),
-                text_uv_a.to_array(),
-            ),
-            wgsl_common::structs::VertexInput::new(
-                self.transform.transform_point2(b).to_array(),
-                color_b.to_array(),
-                uv_b.to_array(),
-                text_uv_b.to_array(),
-            ),
-            wgsl_common::structs::VertexInput::new(
-                self.transform.transform_point2(c).to_array(),
-                color_c.to_array(),
-                uv_c.to_array(),
-                text_uv_c.to_array(),
-            ),
-        ]);
+        self.raw_indexed(
+            [
+                self.vertex(a, color_a, uv_a, text_uv_a),
+                self.vertex(b, color_b, uv_b, text_uv_b),
+                self.vertex(c, color_c, uv_c, text_uv_c),
+            ],
+            [0, 1, 2],
+        );
+    }
+    /// makes a vertex with the current transform applied
+    pub(crate) fn vertex(
+        &self,
+        pos: Vec2,
+        color: Color,
+        uv: Vec2,
+        text_uv: Vec2,
+    ) -> wgsl_common::structs::VertexInput {
+        wgsl_common::structs::VertexInput::new(
+            self.transform.transform_point2(pos).to_array(),
+            color.to_array(),
+            uv.to_array(),
+            text_uv.to_array(),
+        )
+    }
+    /// adds already transformed vertices, `indices` are relative to the first of them
+    pub(crate) fn raw_indexed(
+        &mut self,
+        vertices: impl IntoIterator<Item = wgsl_common::structs::VertexInput>,
+        indices: impl IntoIterator<Item = u32>,
+    ) {
+        let base = self.ctx.inner.vertices.len() as u32;
+        self.ctx.inner.vertices.extend(vertices);
+        self.ctx
+            .inner
+            .indices
+            .extend(indices.into_iter().map(|i| base + i));
+    }
+    /// adds glyph quads with the transform each one is drawn with. plain quads become normal
+    /// vertices and the rest instances for the glyph pipeline, which are drawn first so effects
+    /// stay below plain glyphs
+    pub(crate) fn glyph_quads(&mut self, quads: impl IntoIterator<Item = (GlyphQuad, Affine2)>) {
+        let (plain, instanced): (Vec<_>, Vec<_>) =
+            quads.into_iter().partition(|(quad, _)| quad.is_plain());
+
+        if !instanced.is_empty() {
+            let first_instance = self.ctx.inner.glyph_instances.len() as u32;
+            self.ctx
+                .inner
+                .glyph_instances
+                .extend(instanced.iter().map(|(quad, t)| quad.instance(*t)));
+
+            self.ctx
+                .inner
+                .passes
+                .last_mut()
+                .unwrap()
+                .calls
+                .push(DrawCall {
+                    start_index: self.ctx.inner.indices.len() as u32,
+                    typ: DrawCallType::Glyphs {
+                        blend_mode: self.current_blend_mode,
+                        reference: self.stencil_reference,
+                        layer: self.current_layer,
+                        first_instance,
+                        instance_count: instanced.len() as u32,
+                    },
+                });
+            self.push_draw_call();
+        }
+
+        for (quad, t) in plain {
+            self.raw_indexed(quad.vertices(t), QUAD_INDICES);
+        }
     }
     pub(crate) fn draw_stroke(&mut self, points: impl ExactSizeIterator<Item = Vec2> + Clone) {
         let n_verts = points.len() as u32 * 2;
@@ -210,22 +349,14 @@ impl<'a> Canvas<'a> {
             })
             .collect_vec();
 
-        for [a, b, c] in (0..n_verts).map(|i| [i, (i + 1) % n_verts, (i + 2) % n_verts]) {
-            self.raw_tri(
-                points[a as usize],
-                points[b as usize],
-                points[c as usize],
-                stroke_color,
-                stroke_color,
-                stroke_color,
-                vec2(-1.0, 0.0),
-                vec2(-1.0, 0.0),
-                vec2(-1.0, 0.0),
-                vec2(-1.0, 0.0),
-                vec2(-1.0, 0.0),
-                vec2(-1.0, 0.0),
-            );
-        }
+        let vertices = points
+            .into_iter()
+            .map(|p| self.vertex(p, stroke_color, vec2(-1.0, 0.0), vec2(-1.0, 0.0)))
+            .collect_vec();
+        self.raw_indexed(
+            vertices,
+            (0..n_verts).flat_map(|i| [i, (i + 1) % n_verts, (i + 2) % n_verts]),
+        );
     }
     pub fn rect<'r>(&'r mut self) -> RectBuilder<'a, 'r> {
         RectBuilder {
@@ -278,11 +409,68 @@ impl<'a> Canvas<'a> {
             h: None,
             size: 16.0,
             line_height: 1.3,
-            family: cosmic_text::Family::SansSerif,
+            family: None,
             weight: cosmic_text::Weight::NORMAL,
             style: cosmic_text::Style::Normal,
             stretch: cosmic_text::Stretch::Normal,
             align: cosmic_text::Align::Left,
+            valign: VerticalAlign::Top,
+            wrap: cosmic_text::Wrap::WordOrGlyph,
+            ellipsis: false,
+            clip: false,
+            outline: None,
+            shadow: None,
+            glow: None,
+            sdf: false,
+        }
+    }
+    /// draws spans of differently styled text laid out together,
+    /// see `TextSpan::parse_markup` to build them from markup
+    pub fn rich_text<'r>(&'r mut self, spans: &'r [TextSpan<'r>]) -> RichTextBuilder<'a, 'r> {
+        RichTextBuilder {
+            canvas: self,
+            spans,
+            x: 0.0,
+            y: 0.0,
+            w: None,
+            h: None,
+            size: 16.0,
+            line_height: 1.3,
+            family: None,
+            weight: cosmic_text::Weight::NORMAL,
+            style: cosmic_text::Style::Normal,
+            stretch: cosmic_text::Stretch::Normal,
+            align: cosmic_text::Align::Left,
+        }
+    }
+    /// draws a single line of text bent along a path, each glyph rotated to follow it
+    pub fn text_on_path<'r>(
+        &'r mut self,
+        string: &'r str,
+        path: TextPath<'r>,
+    ) -> TextOnPathBuilder<'a, 'r> {
+        TextOnPathBuilder {
+            canvas: self,
+            text: string,
+            path,
+            offset: 0.0,
+            baseline: 0.0,
+            size: 16.0,
+            line_height: 1.3,
+            family: None,
+            weight: cosmic_text::Weight::NORMAL,
+            style: cosmic_text::Style::Normal,
+            stretch: cosmic_text::Stretch::Normal,
+            align: cosmic_text::Align::Left,
+            sdf: false,
+        }
+    }
+    /// draws a mesh made with `Context::create_mesh` with the current transform and texture
+    pub fn mesh<'r>(&'r mut self, mesh: MeshKey) -> MeshBuilder<'a, 'r> {
+        MeshBuilder {
+            canvas: self,
+            mesh,
+            tint: false,
         }
     }
 
@@ -298,7 +486,7 @@ impl<'a> Canvas<'a> {
             .unwrap()
             .calls
             .push(DrawCall {
-                start_vertex: self.ctx.inner.vertices.len() as u32,
+                start_index: self.ctx.inner.indices.len() as u32,
                 typ: DrawCallType::ClipStart {
                     reference: self.stencil_reference,
                 },
@@ -307,21 +495,7 @@ impl<'a> Canvas<'a> {
 
         let cr = clip_cb(self);
 
-        self.ctx
-            .inner
-            .passes
-            .last_mut()
-            .unwrap()
-            .calls
-            .push(DrawCall {
-                start_vertex: self.ctx.inner.vertices.len() as u32,
-                typ: DrawCallType::Draw {
-                    blend_mode: self.current_blend_mode,
-                    set_texture: None,
-                    reference: self.stencil_reference,
-                    end_clip_reference: None,
-                },
-            });
+        self.push_draw_call();
 
         let fr = draw_cb(self);
 
@@ -332,12 +506,13 @@ impl<'a> Canvas<'a> {
             .unwrap()
             .calls
             .push(DrawCall {
-                start_vertex: self.ctx.inner.vertices.len() as u32,
+                start_index: self.ctx.inner.indices.len() as u32,
                 typ: DrawCallType::Draw {
                     blend_mode: self.current_blend_mode,
-                    set_texture: None,
+                    texture: self.current_texture,
                     reference: self.stencil_reference - 1,
                     end_clip_reference: Some(self.stencil_reference),
+                    layer: self.current_layer,
                 },
             });
         self.stencil_reference -= 1;
@@ -345,27 +520,42 @@ impl<'a> Canvas<'a> {
         (cr, fr)
     }
 
+    /// clears the whole canvas to `fill_color`
     pub fn clear(&mut self) {
-        self.raw_tri(
-            vec2(0.0, 0.0),
-            vec2(
-                self.ctx.inner.gpu_data.surface_config.width as f32 * 2.0,
-                0.0,
-            ),
-            vec2(
-                0.0,
-                self.ctx.inner.gpu_data.surface_config.height as f32 * 2.0,
-            ),
-            self.fill_color,
-            self.fill_color,
-            self.fill_color,
-            vec2(-1.0, 0.0),
-            vec2(-1.0, 0.0),
-            vec2(-1.0, 0.0),
-            vec2(-1.0, 0.0),
-            vec2(-1.0, 0.0),
-            vec2(-1.0, 0.0),
-        );
+        if self.stencil_reference != 0 {
+            panic!("cleared canvas during clipped draw")
+        }
+
+        let color = self.fill_color;
+        let start_index = self.ctx.inner.indices.len() as u32;
+
+        let pass = self.ctx.inner.passes.last_mut().unwrap();
+        // sprites, meshes and instanced glyphs add no indices, so only a pass that is still just
+        // its initial draw call is really empty
+        let is_empty = match pass.calls.as_slice() {
+            [call] => {
+                matches!(call.typ, DrawCallType::Draw { .. }) && call.start_index == start_index
+            }
+            _ => false,
+        };
+        if is_empty {
+            pass.clear = Some(color);
+        } else {
+            self.ctx.inner.passes.push(RenderPass {
+                target_canvas: self.key,
+                clear: Some(color),
+                calls: vec![DrawCall {
+                    start_index,
+                    typ: DrawCallType::Draw {
+                        blend_mode: self.current_blend_mode,
+                        texture: self.current_texture,
+                        reference: 0,
+                        end_clip_reference: None,
+                        layer: self.current_layer,
+                    },
+                }],
+            });
+        }
     }
 }
 
@@ -0,0 +1,94 @@
+use glam::{Affine2, Vec2, vec2};
+
+use crate::{
+    Canvas, Color,
+    context::{DrawCall, DrawCallType, texture::TextureKey},
+    render::shaders::wgsl_sprite,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpriteInstance {
+    /// maps the unit square (0, 0) to (1, 1) onto the canvas, the canvas transform is applied after
+    pub transform: Affine2,
+    /// (position, size) in pixels of the part of the texture to draw, the whole texture if `None`
+    pub region: Option<(Vec2, Vec2)>,
+    pub color: Color,
+}
+
+impl SpriteInstance {
+    /// a `w` by `h` sprite with its top left corner at `x`, `y`
+    pub fn new(x: f32, y: f32, w: f32, h: f32) -> Self {
+        Self {
+            transform: Affine2::from_scale_angle_translation(vec2(w, h), 0.0, vec2(x, y)),
+            region: None,
+            color: Color::rgb(1.0, 1.0, 1.0),
+        }
+    }
+    /// a `w` by `h` sprite centered on `x`, `y` and rotated by `angle` around it
+    pub fn centered(x: f32, y: f32, w: f32, h: f32, angle: f32) -> Self {
+        Self {
+            transform: Affine2::from_angle_translation(angle, vec2(x, y))
+                * Affine2::from_scale_angle_translation(vec2(w, h), 0.0, vec2(-w, -h) / 2.0),
+            region: None,
+            color: Color::rgb(1.0, 1.0, 1.0),
+        }
+    }
+    #[inline]
+    pub fn region(mut self, pos: Vec2, size: Vec2) -> Self {
+        self.region = Some((pos, size));
+        self
+    }
+    #[inline]
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+}
+
+impl<'a> Canvas<'a> {
+    /// draws many copies of `texture` in a single instanced draw call,
+    /// the quads are expanded on the GPU instead of going through `raw_tri`
+    pub fn sprites(&mut self, texture: TextureKey, sprites: &[SpriteInstance]) {
+        if sprites.is_empty() {
+            return;
+        }
+
+        let texture_size = self.ctx.texture_dimensions(texture).as_vec2();
+        let first_instance = self.ctx.inner.sprite_instances.len() as u32;
+
+        let transform = self.transform;
+        self.ctx
+            .inner
+            .sprite_instances
+            .extend(sprites.iter().map(|sprite| {
+                let t = transform * sprite.transform;
+                let (pos, size) = sprite.region.unwrap_or((Vec2::ZERO, texture_size));
+                wgsl_sprite::structs::SpriteInstanceInput::new(
+                    t.matrix2.x_axis.to_array(),
+                    t.matrix2.y_axis.to_array(),
+                    t.translation.to_array(),
+                    [pos.x, pos.y, size.x, size.y],
+                    sprite.color.to_array(),
+                )
+            }));
+
+        self.ctx
+            .inner
+            .passes
+            .last_mut()
+            .unwrap()
+            .calls
+            .push(DrawCall {
+                start_index: self.ctx.inner.indices.len() as u32,
+                typ: DrawCallType::Sprites {
+                    blend_mode: self.current_blend_mode,
+                    texture,
+                    reference: self.stencil_reference,
+                    layer: self.current_layer,
+                    first_instance,
+                    instance_count: sprites.len() as u32,
+                },
+            });
+        self.push_draw_call();
+    }
+}
@@ -0,0 +1,534 @@
+use cosmic_text::{Action, Change, Edit, Motion, Selection};
+use glam::{Vec2, vec2};
+use winit::{
+    event::{KeyEvent, MouseButton},
+    keyboard::{Key, NamedKey},
+};
+
+use crate::{
+    canvas::{Canvas, QUAD_INDICES, color::Color},
+    context::{Context, fonts::default_family},
+    render::text::{find_closest_attrs, glyph::prepare_glyph},
+};
+
+/// clicks closer together than this count as double and triple clicks
+const MULTI_CLICK_TIME: f64 = 0.4;
+/// the caret is shown and hidden for this long each
+const CARET_BLINK_TIME: f64 = 0.5;
+
+/// an editable single or multi line text field.
+///
+/// keep it in the app state and forward `key_event`, `text_input`, `ime_preedit` and `mouse_input`
+/// to it from the matching `AppState` callbacks, call `update` once per frame and `draw` it.
+/// mouse positions are compared with where it was last drawn, so draw it without a transform
+pub struct TextInput {
+    editor: cosmic_text::Editor<'static>,
+    attrs: cosmic_text::AttrsOwned,
+    multiline: bool,
+    focused: bool,
+
+    pub text_color: Color,
+    pub selection_color: Color,
+    pub caret_color: Color,
+
+    /// position and size it was last drawn at
+    bounds: (Vec2, Vec2),
+    dragging: bool,
+    last_click: Option<(f64, u8)>,
+    last_edit: f64,
+    preedit: String,
+
+    undo: Vec<Change>,
+    redo: Vec<Change>,
+    /// the last undo entry is typed text that the next typed text gets merged into
+    typing: bool,
+}
+
+impl TextInput {
+    pub fn new(ctx: &mut Context, size: f32, multiline: bool) -> Self {
+        let font_system = &mut ctx.gpu_data.font_system;
+        let attrs = cosmic_text::AttrsOwned::new(&find_closest_attrs(
+            font_system.db(),
+            default_family(&ctx.default_font_family),
+            cosmic_text::Weight::NORMAL,
+            cosmic_text::Style::Normal,
+            cosmic_text::Stretch::Normal,
+        ));
+        let mut buffer =
+            cosmic_text::Buffer::new(font_system, cosmic_text::Metrics::relative(size, 1.3));
+        buffer.set_wrap(
+            font_system,
+            if multiline {
+                cosmic_text::Wrap::WordOrGlyph
+            } else {
+                cosmic_text::Wrap::None
+            },
+        );
+        buffer.set_text(
+            font_system,
+            "",
+            &attrs.as_attrs(),
+            cosmic_text::Shaping::Advanced,
+        );
+
+        Self {
+            editor: cosmic_text::Editor::new(buffer),
+            attrs,
+            multiline,
+            focused: false,
+            text_color: Color::rgb(1.0, 1.0, 1.0),
+            selection_color: Color::rgba(0.3, 0.5, 0.9, 0.5),
+            caret_color: Color::rgb(1.0, 1.0, 1.0),
+            bounds: (Vec2::ZERO, Vec2::ZERO),
+            dragging: false,
+            last_click: None,
+            last_edit: 0.0,
+            preedit: String::new(),
+            undo: vec![],
+            redo: vec![],
+            typing: false,
+        }
+    }
+
+    pub fn text(&self) -> String {
+        self.editor.with_buffer(|buffer| {
+            buffer
+                .lines
+                .iter()
+                .map(|line| line.text())
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+    }
+    /// replaces the text, moving the caret to the end and clearing the undo history
+    pub fn set_text(&mut self, ctx: &mut Context, text: &str) {
+        let text = if self.multiline {
+            text.into()
+        } else {
+            text.replace(['\n', '\r'], " ")
+        };
+        let attrs = self.attrs.as_attrs();
+        self.editor.with_buffer_mut(|buffer| {
+            buffer.set_text(
+                ctx.font_system(),
+                &text,
+                &attrs,
+                cosmic_text::Shaping::Advanced,
+            )
+        });
+        self.editor.set_selection(Selection::None);
+        self.editor.set_cursor(cosmic_text::Cursor::default());
+        self.editor
+            .action(ctx.font_system(), Action::Motion(Motion::BufferEnd));
+        self.undo.clear();
+        self.redo.clear();
+        self.typing = false;
+    }
+    /// the selected text, `None` if nothing is selected
+    pub fn selected_text(&self) -> Option<String> {
+        self.editor.copy_selection()
+    }
+    pub fn select_all(&mut self, ctx: &mut Context) {
+        self.editor.set_cursor(cosmic_text::Cursor::default());
+        self.editor
+            .set_selection(Selection::Normal(cosmic_text::Cursor::default()));
+        self.editor
+            .action(ctx.font_system(), Action::Motion(Motion::BufferEnd));
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+    /// focused inputs receive keys and text and turn on the ime
+    pub fn set_focused(&mut self, ctx: &mut Context, focused: bool) {
+        if focused != self.focused {
+            self.focused = focused;
+            self.dragging = false;
+            self.preedit.clear();
+            ctx.set_ime_allowed(focused);
+        }
+        self.last_edit = ctx.elapsed_time();
+    }
+
+    pub fn undo(&mut self, ctx: &mut Context) {
+        let Some(mut change) = self.undo.pop() else {
+            return;
+        };
+        change.reverse();
+        self.editor.set_selection(Selection::None);
+        self.editor.apply_change(&change);
+        change.reverse();
+        self.redo.push(change);
+        self.typing = false;
+        self.changed(ctx);
+    }
+    pub fn redo(&mut self, ctx: &mut Context) {
+        let Some(change) = self.redo.pop() else {
+            return;
+        };
+        self.editor.set_selection(Selection::None);
+        self.editor.apply_change(&change);
+        self.undo.push(change);
+        self.typing = false;
+        self.changed(ctx);
+    }
+
+    /// handles editing keys and shortcuts while focused, returns whether the key was used.
+    /// enter isn't used by single line inputs so the app can treat it as submitting
+    pub fn key_event(&mut self, event: &KeyEvent, ctx: &mut Context) -> bool {
+        if !self.focused || !event.state.is_pressed() || ctx.is_ime_composing() {
+            return false;
+        }
+        let modifiers = ctx.modifiers();
+        let shortcut = modifiers.control_key() || modifiers.super_key();
+        let shift = modifiers.shift_key();
+
+        let motion = match &event.logical_key {
+            Key::Named(NamedKey::ArrowLeft) if shortcut => Some(Motion::LeftWord),
+            Key::Named(NamedKey::ArrowRight) if shortcut => Some(Motion::RightWord),
+            Key::Named(NamedKey::ArrowLeft) => Some(Motion::Left),
+            Key::Named(NamedKey::ArrowRight) => Some(Motion::Right),
+            Key::Named(NamedKey::ArrowUp) => Some(Motion::Up),
+            Key::Named(NamedKey::ArrowDown) => Some(Motion::Down),
+            Key::Named(NamedKey::Home) if shortcut => Some(Motion::BufferStart),
+            Key::Named(NamedKey::End) if shortcut => Some(Motion::BufferEnd),
+            Key::Named(NamedKey::Home) => Some(Motion::Home),
+            Key::Named(NamedKey::End) => Some(Motion::End),
+            Key::Named(NamedKey::PageUp) => Some(Motion::PageUp),
+            Key::Named(NamedKey::PageDown) => Some(Motion::PageDown),
+            _ => None,
+        };
+        if let Some(motion) = motion {
+            self.move_caret(ctx, motion, shift);
+            return true;
+        }
+
+        match &event.logical_key {
+            Key::Named(NamedKey::Backspace) => {
+                self.edit(ctx, |e, fs| e.action(fs, Action::Backspace))
+            }
+            Key::Named(NamedKey::Delete) => self.edit(ctx, |e, fs| e.action(fs, Action::Delete)),
+            Key::Named(NamedKey::Enter) if self.multiline => {
+                self.edit(ctx, |e, fs| e.action(fs, Action::Enter))
+            }
+            Key::Named(NamedKey::Escape) => {
+                self.editor.action(ctx.font_system(), Action::Escape);
+            }
+            Key::Character(c) if shortcut => match c.to_lowercase().as_str() {
+                "a" => self.select_all(ctx),
+                "c" => self.copy(ctx),
+                "x" => {
+                    self.copy(ctx);
+                    self.edit(ctx, |e, _| _ = e.delete_selection());
+                }
+                "v" => self.paste(ctx),
+                "z" if shift => self.redo(ctx),
+                "z" => self.undo(ctx),
+                "y" => self.redo(ctx),
+                _ => return false,
+            },
+            _ => return false,
+        }
+        true
+    }
+    /// inserts typed or ime committed text while focused
+    pub fn text_input(&mut self, text: &str, ctx: &mut Context) {
+        let modifiers = ctx.modifiers();
+        if !self.focused || modifiers.control_key() || modifiers.super_key() {
+            return;
+        }
+        self.preedit.clear();
+        let typing = self.typing && self.editor.selection_bounds().is_none();
+        let undo_len = self.undo.len();
+        self.edit(ctx, |e, _| e.insert_string(text, None));
+        // only an entry this edit added can be merged, an edit that changed nothing adds none
+        let recorded = self.undo.len() == undo_len + 1;
+        if typing && recorded && undo_len >= 1 {
+            let change = self.undo.pop().unwrap();
+            self.undo.last_mut().unwrap().items.extend(change.items);
+        }
+        self.typing = typing || recorded;
+    }
+    /// shows the ime's uncommitted text at the caret
+    pub fn ime_preedit(&mut self, text: &str, _cursor: Option<(usize, usize)>, _ctx: &mut Context) {
+        if self.focused {
+            self.preedit = text.into();
+        }
+    }
+    /// focuses on clicks inside and unfocuses on clicks outside, returns whether the click was inside.
+    /// double clicks select words, triple clicks select lines and shift clicks extend the selection
+    pub fn mouse_input(&mut self, button: MouseButton, pressed: bool, ctx: &mut Context) -> bool {
+        if button != MouseButton::Left {
+            return false;
+        }
+        if !pressed {
+            self.dragging = false;
+            return false;
+        }
+
+        let mouse = ctx.mouse_pos();
+        let (pos, size) = self.bounds;
+        let inside = mouse.cmpge(pos).all() && mouse.cmplt(pos + size).all();
+        self.set_focused(ctx, inside);
+        if !inside {
+            return false;
+        }
+
+        let now = ctx.elapsed_time();
+        let clicks = match self.last_click {
+            Some((time, clicks)) if now - time < MULTI_CLICK_TIME => clicks % 3 + 1,
+            _ => 1,
+        };
+        self.last_click = Some((now, clicks));
+
+        let (x, y) = self.buffer_point(mouse);
+        let action = match clicks {
+            _ if ctx.modifiers().shift_key() => Action::Drag { x, y },
+            1 => Action::Click { x, y },
+            2 => Action::DoubleClick { x, y },
+            _ => Action::TripleClick { x, y },
+        };
+        self.editor.action(ctx.font_system(), action);
+        self.dragging = true;
+        self.typing = false;
+        true
+    }
+    /// extends the selection while dragging, scrolls with the mouse wheel while hovered and
+    /// tells the ime where the caret is
+    pub fn update(&mut self, ctx: &mut Context) {
+        let mouse = ctx.mouse_pos();
+        if self.dragging {
+            if ctx.is_mouse_pressed(MouseButton::Left) {
+                let (x, y) = self.buffer_point(mouse);
+                self.editor.action(ctx.font_system(), Action::Drag { x, y });
+            } else {
+                self.dragging = false;
+            }
+        }
+
+        let (pos, size) = self.bounds;
+        let wheel = ctx.mouse_wheel_delta().y;
+        if self.multiline && wheel != 0.0 && mouse.cmpge(pos).all() && mouse.cmplt(pos + size).all()
+        {
+            self.editor.with_buffer_mut(|buffer| {
+                let mut scroll = buffer.scroll();
+                scroll.vertical -= wheel * buffer.metrics().line_height;
+                buffer.set_scroll(scroll);
+            });
+        }
+
+        // another input losing focus in the same click may have turned the ime off
+        if self.focused {
+            ctx.set_ime_allowed(true);
+        }
+        if self.focused
+            && let Some((x, y)) = self.editor.cursor_position()
+        {
+            let (line_height, horizontal) = self
+                .editor
+                .with_buffer(|buffer| (buffer.metrics().line_height, buffer.scroll().horizontal));
+            ctx.set_ime_cursor_area(
+                pos + vec2(x as f32 - horizontal, y as f32),
+                vec2(1.0, line_height),
+            );
+        }
+    }
+
+    /// draws the text, selection and caret clipped to the given box, and remembers it for mouse input.
+    /// single line inputs scroll sideways to keep the caret visible, multi line inputs wrap
+    pub fn draw(&mut self, canvas: &mut Canvas, x: f32, y: f32, w: f32, h: f32) {
+        self.bounds = (vec2(x, y), vec2(w, h));
+        let font_system = &mut canvas.ctx.inner.gpu_data.font_system;
+        self.editor.with_buffer_mut(|buffer| {
+            buffer.set_size(font_system, Some(w), self.multiline.then_some(h))
+        });
+        self.editor.shape_as_needed(font_system, true);
+
+        let caret_visible = self.focused
+            && (((canvas.ctx.elapsed_time() - self.last_edit) / CARET_BLINK_TIME) as u64)
+                .is_multiple_of(2);
+        let selection = self.editor.selection_bounds();
+        let caret = self.editor.cursor_position();
+        let (text_color, selection_color, caret_color) =
+            (self.text_color, self.selection_color, self.caret_color);
+        let preedit = &self.preedit;
+
+        canvas.with_state(|canvas| {
+            canvas.clipped(
+                |canvas| {
+                    canvas.draw_fill = true;
+                    canvas.draw_stroke = false;
+                    canvas.rect().xywh(x, y, w, h).draw();
+                },
+                |canvas| {
+                    canvas.draw_fill = true;
+                    canvas.draw_stroke = false;
+                    self.editor.with_buffer(|buffer| {
+                        let offset = vec2(x - buffer.scroll().horizontal, y);
+                        for run in buffer.layout_runs() {
+                            if let Some((start, end)) = selection
+                                && (start.line..=end.line).contains(&run.line_i)
+                                && let Some((sel_x, mut sel_w)) =
+                                    run.highlight(start, end).or_else(|| {
+                                        // empty lines inside the selection have no glyphs
+                                        (run.glyphs.is_empty() && end.line > run.line_i)
+                                            .then_some((0.0, 0.0))
+                                    })
+                            {
+                                if end.line > run.line_i {
+                                    sel_w = sel_w.max(buffer.size().0.unwrap_or(0.0) - sel_x);
+                                }
+                                canvas.fill_color = selection_color;
+                                canvas
+                                    .rect()
+                                    .xywh(
+                                        offset.x + sel_x,
+                                        offset.y + run.line_top,
+                                        sel_w,
+                                        run.line_height,
+                                    )
+                                    .draw();
+                            }
+                            for glyph in run.glyphs {
+                                canvas.draw_glyph(
+                                    glyph.physical((0.0, 0.0), 1.0),
+                                    run.line_y,
+                                    text_color,
+                                    offset,
+                                );
+                            }
+                        }
+
+                        if let Some((caret_x, caret_y)) = caret {
+                            let line_height = buffer.metrics().line_height;
+                            let caret_pos = offset + vec2(caret_x as f32, caret_y as f32);
+                            if !preedit.is_empty() {
+                                canvas.fill_color = text_color;
+                                let size = buffer.metrics().font_size;
+                                let text_w = canvas
+                                    .text(preedit)
+                                    .xy(caret_pos.x, caret_pos.y)
+                                    .size(size)
+                                    .measure()
+                                    .x;
+                                canvas
+                                    .text(preedit)
+                                    .xy(caret_pos.x, caret_pos.y)
+                                    .size(size)
+                                    .draw();
+                                canvas
+                                    .rect()
+                                    .xywh(caret_pos.x, caret_pos.y + line_height - 2.0, text_w, 1.0)
+                                    .draw();
+                            } else if caret_visible {
+                                canvas.fill_color = caret_color;
+                                canvas
+                                    .rect()
+                                    .xywh(caret_pos.x, caret_pos.y, 1.0, line_height)
+                                    .draw();
+                            }
+                        }
+                    });
+                },
+            );
+        });
+    }
+
+    /// a point in canvas space relative to the scrolled buffer, as the editor's actions expect
+    fn buffer_point(&self, point: Vec2) -> (i32, i32) {
+        let horizontal = self.editor.with_buffer(|buffer| buffer.scroll().horizontal);
+        let p = point - self.bounds.0 + vec2(horizontal, 0.0);
+        (p.x as i32, p.y as i32)
+    }
+    fn move_caret(&mut self, ctx: &mut Context, motion: Motion, select: bool) {
+        if select {
+            if self.editor.selection() == Selection::None {
+                self.editor
+                    .set_selection(Selection::Normal(self.editor.cursor()));
+            }
+        } else if let Some((start, end)) = self.editor.selection_bounds()
+            && matches!(motion, Motion::Left | Motion::Right)
+        {
+            // collapses the selection to the side the caret moves towards, like most text fields
+            self.editor.set_selection(Selection::None);
+            self.editor
+                .set_cursor(if motion == Motion::Left { start } else { end });
+            self.changed(ctx);
+            return;
+        } else {
+            self.editor.set_selection(Selection::None);
+        }
+        self.editor
+            .action(ctx.font_system(), Action::Motion(motion));
+        self.typing = false;
+        self.changed(ctx);
+    }
+    /// runs an edit, recording it as one undo step
+    fn edit(
+        &mut self,
+        ctx: &mut Context,
+        f: impl FnOnce(&mut cosmic_text::Editor<'static>, &mut cosmic_text::FontSystem),
+    ) {
+        self.editor.start_change();
+        f(&mut self.editor, ctx.font_system());
+        if let Some(change) = self.editor.finish_change()
+            && !change.items.is_empty()
+        {
+            self.undo.push(change);
+            self.redo.clear();
+        }
+        self.typing = false;
+        self.changed(ctx);
+    }
+    fn copy(&mut self, ctx: &mut Context) {
+        if let Some(text) = self.editor.copy_selection() {
+            ctx.set_clipboard_text(&text);
+        }
+    }
+    fn paste(&mut self, ctx: &mut Context) {
+        let Some(text) = ctx.clipboard_text() else {
+            return;
+        };
+        let text = if self.multiline {
+            text.replace("\r\n", "\n")
+        } else {
+            text.replace(['\n', '\r'], " ")
+        };
+        self.edit(ctx, |e, _| e.insert_string(&text, None));
+    }
+    /// restarts the caret blink so it stays visible while editing
+    fn changed(&mut self, ctx: &mut Context) {
+        self.last_edit = ctx.elapsed_time();
+    }
+}
+
+impl Canvas<'_> {
+    /// draws a glyph from a shaped buffer with the current transform, `offset` moves the whole layout
+    pub(crate) fn draw_glyph(
+        &mut self,
+        physical: cosmic_text::PhysicalGlyph,
+        line_y: f32,
+        color: Color,
+        offset: Vec2,
+    ) {
+        if let Some(vertices) = prepare_glyph(
+            physical,
+            line_y,
+            &mut self.ctx.inner.gpu_data,
+            color.to_array(),
+            offset.x,
+            offset.y,
+        ) {
+            let transform = self.transform;
+            self.raw_indexed(
+                vertices.map(|mut v| {
+                    v.pos = transform
+                        .transform_point2(Vec2::from_array(v.pos))
+                        .to_array();
+                    v
+                }),
+                QUAD_INDICES,
+            );
+        }
+    }
+}
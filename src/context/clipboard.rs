@@ -0,0 +1,20 @@
+use crate::context::Context;
+
+impl Context {
+    /// the system clipboard, connected on first use. `None` if there is no clipboard to connect to
+    fn clipboard(&mut self) -> Option<&mut arboard::Clipboard> {
+        if self.clipboard.is_none() {
+            self.clipboard = arboard::Clipboard::new().ok();
+        }
+        self.clipboard.as_mut()
+    }
+    /// the text on the system clipboard, `None` if it's empty, not text or unavailable
+    pub fn clipboard_text(&mut self) -> Option<String> {
+        self.clipboard()?.get_text().ok()
+    }
+    /// puts text on the system clipboard, returns false if the clipboard is unavailable
+    pub fn set_clipboard_text(&mut self, text: &str) -> bool {
+        self.clipboard()
+            .is_some_and(|clipboard| clipboard.set_text(text).is_ok())
+    }
+}
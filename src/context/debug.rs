@@ -0,0 +1,165 @@
+use std::{collections::VecDeque, time::Instant};
+
+use winit::keyboard::{KeyCode, PhysicalKey};
+
+use crate::{
+    canvas::{Canvas, color::Color},
+    context::{Context, EitherKey},
+};
+
+/// how many frame times the graph shows
+const GRAPH_FRAMES: usize = 120;
+
+pub struct DebugOverlay {
+    pub(crate) visible: bool,
+    pub(crate) hotkey: Option<EitherKey>,
+    /// seconds per frame, newest last
+    pub(crate) frame_times: VecDeque<f32>,
+    /// fixed ticks of the last second, used to measure the achieved rate
+    pub(crate) fixed_ticks: VecDeque<Instant>,
+}
+
+impl Default for DebugOverlay {
+    fn default() -> Self {
+        Self {
+            visible: false,
+            hotkey: Some(EitherKey::Physical(PhysicalKey::Code(KeyCode::F3))),
+            frame_times: VecDeque::with_capacity(GRAPH_FRAMES),
+            fixed_ticks: VecDeque::new(),
+        }
+    }
+}
+
+impl DebugOverlay {
+    pub(crate) fn record_frame(&mut self, delta_time: f32) {
+        if self.frame_times.len() == GRAPH_FRAMES {
+            self.frame_times.pop_front();
+        }
+        self.frame_times.push_back(delta_time);
+    }
+    pub(crate) fn record_fixed_tick(&mut self) {
+        let now = Instant::now();
+        self.fixed_ticks.push_back(now);
+        while self
+            .fixed_ticks
+            .front()
+            .is_some_and(|t| now.duration_since(*t).as_secs_f32() > 1.0)
+        {
+            self.fixed_ticks.pop_front();
+        }
+    }
+}
+
+impl Context {
+    pub fn debug_overlay_visible(&self) -> bool {
+        self.debug_overlay.visible
+    }
+    pub fn set_debug_overlay_visible(&mut self, visible: bool) {
+        self.debug_overlay.visible = visible;
+    }
+    /// the key that toggles the debug overlay, F3 by default. `None` disables the hotkey
+    pub fn set_debug_overlay_key(&mut self, key: Option<impl Into<EitherKey>>) {
+        self.debug_overlay.hotkey = key.map(Into::into);
+    }
+}
+
+/// draws fps, a frame time graph, the fixed update rate, draw calls and texture memory
+/// in the top left corner of the window
+pub(crate) fn draw_debug_overlay(canvas: &mut Canvas) {
+    let ctx = &canvas.ctx.inner;
+    let stats = ctx.frame_stats;
+    let frame_times = ctx
+        .debug_overlay
+        .frame_times
+        .iter()
+        .copied()
+        .collect::<Vec<_>>();
+    let fixed_rate = ctx.debug_overlay.fixed_ticks.len();
+    let requested_rate = ctx.fixed_update_rate();
+
+    let average = frame_times.iter().sum::<f32>() / frame_times.len().max(1) as f32;
+    let fps = if average > 0.0 { 1.0 / average } else { 0.0 };
+    let mb = |bytes: u64| bytes as f32 / (1024.0 * 1024.0);
+
+    let lines = [
+        format!("{fps:.0} fps  {:.2} ms", average * 1000.0),
+        format!("fixed {fixed_rate} / {requested_rate} Hz"),
+        format!(
+            "{} draw calls  {} passes  {} vertices",
+            stats.draw_calls, stats.render_passes, stats.vertices
+        ),
+        format!(
+            "textures {:.1} MB  atlases {:.1} MB",
+            mb(stats.texture_memory_bytes),
+            mb(stats.mask_atlas.memory_bytes
+                + stats.color_atlas.memory_bytes
+                + stats.sdf_atlas.memory_bytes)
+        ),
+        match stats.gpu_time {
+            Some(gpu_time) => format!(
+                "cpu {:.2} ms  gpu {:.2} ms",
+                (stats.draw_time + stats.submit_time).as_secs_f32() * 1000.0,
+                gpu_time.as_secs_f32() * 1000.0
+            ),
+            None => format!(
+                "cpu {:.2} ms",
+                (stats.draw_time + stats.submit_time).as_secs_f32() * 1000.0
+            ),
+        },
+    ];
+
+    let (x, y, w) = (8.0, 8.0, 280.0);
+    let (padding, line_height, text_size) = (8.0, 18.0, 14.0);
+    let (graph_w, graph_h) = (w - padding * 2.0, 48.0);
+    let h = padding * 3.0 + graph_h + line_height * lines.len() as f32;
+
+    canvas.draw_stroke = false;
+    canvas.fill_color = Color::rgba(0.0, 0.0, 0.0, 0.75);
+    canvas.rect().xywh(x, y, w, h).draw();
+
+    // frame time graph, the line marks 60 fps and bars are scaled to fit the slowest frame
+    let graph_x = x + padding;
+    let graph_y = y + padding;
+    let max_time = frame_times.iter().copied().fold(1.0 / 30.0, f32::max);
+    let bar_w = graph_w / GRAPH_FRAMES as f32;
+    let offset = GRAPH_FRAMES - frame_times.len();
+    for (i, time) in frame_times.iter().enumerate() {
+        let bar_h = time / max_time * graph_h;
+        canvas.fill_color = if *time > 1.0 / 30.0 {
+            Color::rgb(0.9, 0.3, 0.3)
+        } else if *time > 1.0 / 55.0 {
+            Color::rgb(0.9, 0.8, 0.3)
+        } else {
+            Color::rgb(0.4, 0.9, 0.4)
+        };
+        canvas
+            .rect()
+            .xywh(
+                graph_x + (offset + i) as f32 * bar_w,
+                graph_y + graph_h - bar_h,
+                bar_w,
+                bar_h,
+            )
+            .draw();
+    }
+    canvas.fill_color = Color::rgba(1.0, 1.0, 1.0, 0.4);
+    canvas
+        .rect()
+        .xywh(
+            graph_x,
+            graph_y + graph_h - (1.0 / 60.0) / max_time * graph_h,
+            graph_w,
+            1.0,
+        )
+        .draw();
+
+    canvas.fill_color = Color::rgb(1.0, 1.0, 1.0);
+    let text_y = graph_y + graph_h + padding;
+    for (i, line) in lines.iter().enumerate() {
+        canvas
+            .text(line)
+            .xy(graph_x, text_y + i as f32 * line_height)
+            .size(text_size)
+            .draw();
+    }
+}
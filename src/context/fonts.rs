@@ -0,0 +1,91 @@
+use std::{fmt, io, path::Path, sync::Arc};
+
+use crate::context::Context;
+
+#[derive(Debug)]
+pub enum FontLoadError {
+    FileNotFound,
+    /// the file exists but couldn't be read
+    Io(io::Error),
+    /// the data isn't a font format that can be read (ttf, otf, ttc)
+    InvalidFont,
+}
+
+impl fmt::Display for FontLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::FileNotFound => write!(f, "font file not found"),
+            Self::Io(e) => write!(f, "failed to read the font file: {e}"),
+            Self::InvalidFont => write!(f, "the data isn't a font that can be read"),
+        }
+    }
+}
+
+impl std::error::Error for FontLoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::FileNotFound | Self::InvalidFont => None,
+        }
+    }
+}
+
+impl Context {
+    /// loads every face in a font file and returns the family name to use with
+    /// `cosmic_text::Family::Name`
+    pub fn load_font_path<P: AsRef<Path>>(&mut self, path: P) -> Result<String, FontLoadError> {
+        let data = std::fs::read(path).map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound => FontLoadError::FileNotFound,
+            _ => FontLoadError::Io(e),
+        })?;
+        self.load_font_data(data)
+    }
+    /// like `load_font_path`, for fonts embedded with `include_bytes!` or loaded some other way
+    pub fn load_font_bytes(&mut self, bytes: &[u8]) -> Result<String, FontLoadError> {
+        self.load_font_data(bytes.to_vec())
+    }
+    fn load_font_data(&mut self, data: Vec<u8>) -> Result<String, FontLoadError> {
+        let db = self.gpu_data.font_system.db_mut();
+        let ids = db.load_font_source(cosmic_text::fontdb::Source::Binary(Arc::new(data)));
+        let family = ids
+            .first()
+            .and_then(|&id| db.face(id))
+            .and_then(|face| face.families.first())
+            .map(|(name, _)| name.clone())
+            .ok_or(FontLoadError::InvalidFont)?;
+
+        // text shaped before may have fallen back to another font for this family
+        self.buffer_cache.clear();
+        Ok(family)
+    }
+
+    /// names of all font families that can be used, sorted
+    pub fn font_families(&self) -> Vec<String> {
+        let mut families = self
+            .gpu_data
+            .font_system
+            .db()
+            .faces()
+            .flat_map(|face| face.families.iter().map(|(name, _)| name.clone()))
+            .collect::<Vec<_>>();
+        families.sort_unstable();
+        families.dedup();
+        families
+    }
+
+    /// the family text is drawn with unless it sets its own, `None` goes back to sans serif
+    pub fn set_default_font_family(&mut self, family: Option<&str>) {
+        self.default_font_family = family.map(str::to_owned);
+    }
+    pub fn default_font_family(&self) -> cosmic_text::Family<'_> {
+        default_family(&self.default_font_family)
+    }
+}
+
+/// the family for `Option<String>` default family settings, split out so it can be used while
+/// other parts of the context are borrowed
+pub(crate) fn default_family(default: &Option<String>) -> cosmic_text::Family<'_> {
+    default
+        .as_deref()
+        .map_or(cosmic_text::Family::SansSerif, cosmic_text::Family::Name)
+}
@@ -0,0 +1,250 @@
+use std::ops::Range;
+
+use crate::context::{Context, DrawCall, DrawCallType, RenderPass};
+
+impl Context {
+    /// see `sort_layers`
+    pub(crate) fn sort_layers(&mut self) {
+        sort_layers(&mut self.passes, &mut self.indices);
+    }
+}
+
+/// reorders the draw calls of every pass by layer, keeping the submission order within a layer.
+/// clip starts and ends are barriers that no draw gets moved across, afterwards neighbouring
+/// calls with the same texture, blend mode and clip state are merged into one
+fn sort_layers(passes: &mut [RenderPass], indices: &mut Vec<u32>) {
+    let uses_layers = passes.iter().any(|pass| {
+        pass.calls.iter().any(|call| match call.typ {
+            DrawCallType::Draw { layer, .. }
+            | DrawCallType::Sprites { layer, .. }
+            | DrawCallType::Glyphs { layer, .. }
+            | DrawCallType::Mesh { layer, .. } => layer != 0,
+            _ => false,
+        })
+    });
+    if !uses_layers {
+        return;
+    }
+
+    let num_indices = indices.len() as u32;
+    let pass_ends = (0..passes.len())
+        .map(|idx| {
+            passes
+                .get(idx + 1)
+                .map(|p| p.calls[0].start_index)
+                .unwrap_or(num_indices)
+        })
+        .collect::<Vec<_>>();
+
+    let mut sorted = Vec::with_capacity(indices.len());
+
+    for (pass, pass_end) in passes.iter_mut().zip(pass_ends) {
+        let mut calls = std::mem::take(&mut pass.calls).into_iter().peekable();
+
+        let mut segments: Vec<Vec<(DrawCall, Range<u32>)>> = vec![];
+        let mut after_barrier = true;
+        while let Some(call) = calls.next() {
+            let end = calls.peek().map(|c| c.start_index).unwrap_or(pass_end);
+            let range = call.start_index..end;
+
+            let is_barrier = match call.typ {
+                DrawCallType::Draw {
+                    end_clip_reference, ..
+                } => end_clip_reference.is_some(),
+                DrawCallType::ClipStart { .. } | DrawCallType::CompositeVirtual => true,
+                DrawCallType::Sprites { .. }
+                | DrawCallType::Glyphs { .. }
+                | DrawCallType::Mesh { .. } => false,
+            };
+            if is_barrier || after_barrier {
+                segments.push(vec![]);
+            }
+            after_barrier = is_barrier;
+            segments.last_mut().unwrap().push((call, range));
+        }
+
+        for segment in &mut segments {
+            segment.sort_by_key(|(call, _)| match call.typ {
+                DrawCallType::Draw { layer, .. }
+                | DrawCallType::Sprites { layer, .. }
+                | DrawCallType::Glyphs { layer, .. }
+                | DrawCallType::Mesh { layer, .. } => layer,
+                _ => 0,
+            });
+        }
+
+        for (mut call, range) in segments.into_iter().flatten() {
+            let mergeable = match (pass.calls.last().map(|c| &c.typ), &call.typ) {
+                (
+                    Some(DrawCallType::Draw {
+                        blend_mode: prev_blend_mode,
+                        texture: prev_texture,
+                        reference: prev_reference,
+                        ..
+                    }),
+                    DrawCallType::Draw {
+                        blend_mode,
+                        texture,
+                        reference,
+                        end_clip_reference: None,
+                        ..
+                    },
+                ) => {
+                    (prev_blend_mode, prev_texture, prev_reference)
+                        == (blend_mode, texture, reference)
+                }
+                _ => false,
+            };
+
+            call.start_index = sorted.len() as u32;
+            sorted.extend_from_slice(&indices[range.start as usize..range.end as usize]);
+            if !mergeable {
+                pass.calls.push(call);
+            }
+        }
+    }
+
+    *indices = sorted;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Color,
+        context::{BlendMode, mesh::MeshKey},
+    };
+
+    fn draw(start_index: u32, layer: i32, reference: u32) -> DrawCall {
+        DrawCall {
+            start_index,
+            typ: DrawCallType::Draw {
+                blend_mode: BlendMode::Normal,
+                texture: None,
+                reference,
+                end_clip_reference: None,
+                layer,
+            },
+        }
+    }
+
+    fn pass(clear: Option<Color>, calls: Vec<DrawCall>) -> RenderPass {
+        RenderPass {
+            target_canvas: Default::default(),
+            clear,
+            calls,
+        }
+    }
+
+    fn layer(call: &DrawCall) -> i32 {
+        match call.typ {
+            DrawCallType::Draw { layer, .. }
+            | DrawCallType::Sprites { layer, .. }
+            | DrawCallType::Glyphs { layer, .. }
+            | DrawCallType::Mesh { layer, .. } => layer,
+            _ => 0,
+        }
+    }
+
+    #[test]
+    fn stable_within_layer() {
+        let mut passes = [pass(
+            None,
+            vec![draw(0, 1, 0), draw(1, 0, 0), draw(2, 1, 0), draw(3, 0, 0)],
+        )];
+        let mut indices = vec![10, 20, 11, 21];
+        sort_layers(&mut passes, &mut indices);
+
+        assert_eq!(indices, [20, 21, 10, 11]);
+        // neighbours with the same state are merged again
+        assert_eq!(passes[0].calls.len(), 1);
+    }
+
+    #[test]
+    fn without_layers_nothing_moves() {
+        let mut passes = [pass(None, vec![draw(0, 0, 0), draw(1, 0, 1)])];
+        let mut indices = vec![1, 2];
+        sort_layers(&mut passes, &mut indices);
+
+        assert_eq!(indices, [1, 2]);
+        assert_eq!(passes[0].calls.len(), 2);
+    }
+
+    #[test]
+    fn clips_are_barriers() {
+        let mut passes = [pass(
+            None,
+            vec![
+                draw(0, 1, 0),
+                DrawCall {
+                    start_index: 1,
+                    typ: DrawCallType::ClipStart { reference: 1 },
+                },
+                draw(2, 0, 1),
+                DrawCall {
+                    start_index: 3,
+                    typ: DrawCallType::Draw {
+                        blend_mode: BlendMode::Normal,
+                        texture: None,
+                        reference: 1,
+                        end_clip_reference: Some(0),
+                        layer: 0,
+                    },
+                },
+                draw(4, 0, 0),
+            ],
+        )];
+        let mut indices = vec![10, 30, 0, 31, 1];
+        sort_layers(&mut passes, &mut indices);
+
+        // the layer 0 draws stay behind the clip start and the clip end
+        assert_eq!(indices, [10, 30, 0, 31, 1]);
+        assert!(matches!(
+            passes[0].calls[1].typ,
+            DrawCallType::ClipStart { .. }
+        ));
+    }
+
+    #[test]
+    fn clears_are_barriers() {
+        let mut passes = [
+            pass(None, vec![draw(0, 1, 0)]),
+            pass(Some(Color::rgb(0.0, 0.0, 0.0)), vec![draw(1, 0, 0)]),
+        ];
+        let mut indices = vec![10, 0];
+        sort_layers(&mut passes, &mut indices);
+
+        assert_eq!(indices, [10, 0]);
+        assert_eq!(passes[1].calls[0].start_index, 1);
+        assert_eq!(layer(&passes[1].calls[0]), 0);
+    }
+
+    #[test]
+    fn mesh_layers_are_sorted() {
+        let mut passes = [pass(
+            None,
+            vec![
+                draw(0, 0, 0),
+                DrawCall {
+                    start_index: 0,
+                    typ: DrawCallType::Mesh {
+                        blend_mode: BlendMode::Normal,
+                        texture: None,
+                        reference: 0,
+                        layer: 1,
+                        mesh: MeshKey::default(),
+                        instance: 0,
+                    },
+                },
+                draw(0, 0, 0),
+            ],
+        )];
+        let mut indices = vec![0, 1, 2];
+        sort_layers(&mut passes, &mut indices);
+
+        // the mesh is the only call on a non-zero layer and still ends up above the later draw
+        let calls = &passes[0].calls;
+        assert_eq!(calls.iter().map(layer).collect::<Vec<_>>(), [0, 1]);
+        assert_eq!(calls[1].start_index, 3);
+    }
+}
@@ -0,0 +1,100 @@
+use glam::Vec2;
+use slotmap::{SlotMap, new_key_type};
+use wgpu::util::DeviceExt;
+
+use crate::{canvas::color::Color, context::Context, render::shaders::wgsl_common};
+
+new_key_type! {
+    pub struct MeshKey;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MeshVertex {
+    pub pos: Vec2,
+    pub color: Color,
+    /// in texture pixels, `None` ignores the texture the mesh is drawn with
+    pub uv: Option<Vec2>,
+}
+
+impl MeshVertex {
+    #[inline]
+    pub fn new(pos: Vec2, color: Color) -> Self {
+        Self {
+            pos,
+            color,
+            uv: None,
+        }
+    }
+    #[inline]
+    pub fn uv(mut self, uv: Vec2) -> Self {
+        self.uv = Some(uv);
+        self
+    }
+}
+
+pub struct Mesh {
+    pub(crate) vertex_buffer: wgpu::Buffer,
+    pub(crate) index_buffer: wgpu::Buffer,
+    pub(crate) index_count: u32,
+    pub(crate) vertices: Vec<wgsl_common::structs::VertexInput>,
+    pub(crate) indices: Vec<u32>,
+}
+
+pub type MeshMap = SlotMap<MeshKey, Mesh>;
+
+impl Context {
+    /// uploads geometry once so it can be drawn every frame with `Canvas::mesh`
+    /// without being rebuilt, `indices` index into `vertices` as triangles.
+    /// a mesh without vertices or indices is kept but draws nothing
+    pub fn create_mesh(&mut self, vertices: &[MeshVertex], indices: &[u32]) -> MeshKey {
+        let vertices = vertices
+            .iter()
+            .map(|v| {
+                wgsl_common::structs::VertexInput::new(
+                    v.pos.to_array(),
+                    v.color.to_array(),
+                    v.uv.unwrap_or(Vec2::new(-1.0, 0.0)).to_array(),
+                    [-1.0, 0.0],
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let mesh = self.upload_mesh(vertices, indices.to_vec());
+        self.meshes.insert(mesh)
+    }
+    pub fn remove_mesh(&mut self, mesh: MeshKey) {
+        self.meshes.remove(mesh);
+    }
+
+    /// the geometry is kept so the mesh can be uploaded again if the device is lost
+    pub(crate) fn upload_mesh(
+        &self,
+        vertices: Vec<wgsl_common::structs::VertexInput>,
+        indices: Vec<u32>,
+    ) -> Mesh {
+        let vertex_buffer =
+            self.gpu_data
+                .device
+                .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: Some("mesh_vertex_buffer"),
+                    contents: bytemuck::cast_slice(&vertices),
+                    usage: wgpu::BufferUsages::VERTEX,
+                });
+        let index_buffer =
+            self.gpu_data
+                .device
+                .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: Some("mesh_index_buffer"),
+                    contents: bytemuck::cast_slice(&indices),
+                    usage: wgpu::BufferUsages::INDEX,
+                });
+
+        Mesh {
+            vertex_buffer,
+            index_buffer,
+            index_count: indices.len() as u32,
+            vertices,
+            indices,
+        }
+    }
+}
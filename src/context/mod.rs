@@ -1,11 +1,21 @@
+mod clipboard;
+pub mod debug;
+pub mod fonts;
+mod layers;
+pub mod mesh;
+mod recovery;
+pub mod stats;
 pub mod texture;
+pub mod time;
+pub mod viewport;
+pub mod window;
 
 use std::{
     any::{Any, TypeId},
     collections::{HashMap, HashSet},
     io::{self, Cursor},
     mem::offset_of,
-    ops::{Deref, DerefMut},
+    ops::{Deref, DerefMut, Range},
     path::Path,
     sync::Arc,
     time::Instant,
@@ -16,14 +26,25 @@ use glam::{UVec2, Vec2, uvec2};
 use image::ImageReader;
 use slotmap::{SlotMap, new_key_type};
 use wgpu::{SurfaceTexture, util::DeviceExt};
-use winit::{event::MouseButton, keyboard::SmolStr, window::Window};
+use winit::{
+    event::MouseButton,
+    keyboard::{ModifiersState, SmolStr},
+    window::Window,
+};
 
 use crate::{
-    canvas::{Canvas, CanvasKey},
-    context::texture::{LoadedTexture, TextureFilter, TextureKey, TextureMap},
+    canvas::{Canvas, CanvasKey, color::Color},
+    context::{
+        debug::DebugOverlay,
+        mesh::{MeshKey, MeshMap},
+        stats::{AtlasStats, FrameStats},
+        texture::{LoadedTexture, TextureFilter, TextureKey, TextureMap},
+        time::FrameTime,
+        viewport::VirtualResolution,
+    },
     render::{
         GPUData, SAMPLE_COUNT,
-        shaders::{wgsl_common, wgsl_draw},
+        shaders::{wgsl_common, wgsl_draw, wgsl_glyph, wgsl_mesh, wgsl_sprite},
         text::{HashableAlign, HashableMetrics},
         texture::TextureBundle,
     },
@@ -34,23 +55,42 @@ pub struct Context {
     pub(crate) gpu_data: GPUData,
     pub(crate) canvas_datas: SlotMap<CanvasKey, CanvasData>,
     pub(crate) loaded_textures: TextureMap,
+    pub(crate) meshes: MeshMap,
+    pub(crate) virtual_resolution: Option<VirtualResolution>,
 
     // maintenance
     pub(crate) render_frame: u64,
     pub(crate) fixed_tick: u64,
     pub(crate) run_mode: ContextRunMode,
+    /// set by `exit`, the event loop exits after the current frame
+    pub(crate) exit_requested: bool,
+    pub(crate) frame_stats: FrameStats,
+    pub(crate) current_stats: FrameStats,
+    pub(crate) last_gpu_time: Option<std::time::Duration>,
+    pub(crate) time: FrameTime,
+    pub(crate) debug_overlay: DebugOverlay,
 
     // drawing related
     pub(crate) current_canvas: Option<CanvasKey>,
     pub(crate) passes: Vec<RenderPass>,
     pub(crate) vertices: Vec<wgsl_common::structs::VertexInput>,
+    pub(crate) indices: Vec<u32>,
+    pub(crate) sprite_instances: Vec<wgsl_sprite::structs::SpriteInstanceInput>,
+    pub(crate) glyph_instances: Vec<wgsl_glyph::structs::GlyphInstanceInput>,
+    pub(crate) mesh_instances: Vec<wgsl_mesh::structs::MeshInstanceInput>,
     pub(crate) buffer_cache: AHashMap<BufferCacheKey, BufferCacheValue>,
+    pub(crate) default_font_family: Option<String>,
 
     // input related
     pub(crate) mouse_pos: Vec2,
     pub(crate) key_info: AHashMap<EitherKey, PressInfo>,
     pub(crate) mouse_button_info: AHashMap<MouseButton, PressInfo>,
     pub(crate) mouse_wheel_info: MouseWheelInfo,
+    pub(crate) ime_allowed: bool,
+    /// the ime has uncommitted preedit text
+    pub(crate) ime_composing: bool,
+    pub(crate) modifiers: ModifiersState,
+    pub(crate) clipboard: Option<arboard::Clipboard>,
 
     // state related
     pub(crate) temp_states: AHashMap<(SmolStr, TypeId), Box<dyn Any + Send + Sync>>,
@@ -85,6 +125,8 @@ pub struct CanvasData {
 
 pub struct RenderPass {
     pub(crate) target_canvas: CanvasKey,
+    /// if set, the target canvas (and its stencil) is cleared to this color when the pass starts
+    pub(crate) clear: Option<Color>,
     pub(crate) calls: Vec<DrawCall>,
 }
 
@@ -97,16 +139,42 @@ pub enum BlendMode {
 pub enum DrawCallType {
     Draw {
         blend_mode: BlendMode,
-        set_texture: Option<TextureKey>,
+        texture: Option<TextureKey>,
         reference: u32,
         end_clip_reference: Option<u32>,
+        layer: i32,
     },
     ClipStart {
         reference: u32,
     },
+    CompositeVirtual,
+    Sprites {
+        blend_mode: BlendMode,
+        texture: TextureKey,
+        reference: u32,
+        layer: i32,
+        first_instance: u32,
+        instance_count: u32,
+    },
+    /// glyphs drawn with the glyph pipeline, see `GlyphQuad`
+    Glyphs {
+        blend_mode: BlendMode,
+        reference: u32,
+        layer: i32,
+        first_instance: u32,
+        instance_count: u32,
+    },
+    Mesh {
+        blend_mode: BlendMode,
+        texture: Option<TextureKey>,
+        reference: u32,
+        layer: i32,
+        mesh: MeshKey,
+        instance: u32,
+    },
 }
 pub struct DrawCall {
-    pub(crate) start_vertex: u32,
+    pub(crate) start_index: u32,
     pub(crate) typ: DrawCallType,
 }
 
@@ -116,6 +184,8 @@ pub struct BufferCacheKey {
     pub(crate) attrs: cosmic_text::AttrsOwned,
     pub(crate) align: HashableAlign,
     pub(crate) text: String,
+    /// byte ranges of `text` shaped with their own attributes, empty for plain text
+    pub(crate) spans: Vec<(Range<usize>, cosmic_text::AttrsOwned)>,
 }
 #[derive(Debug, Clone)]
 pub struct BufferCacheValue {
@@ -182,17 +252,14 @@ impl Context {
     pub(crate) fn reset_draw(&mut self) {
         self.passes.clear();
         self.vertices.clear();
-        self.vertices.extend(
-            [
-                [0.0, 0.0],
-                [self.gpu_data.surface_config.width as f32 * 2.0, 0.0],
-                [0.0, self.gpu_data.surface_config.height as f32 * 2.0],
-            ]
-            .map(|pos| wgsl_common::structs::VertexInput::new(pos, [1.0; 4], [-1.0; 2], [-1.0; 2])),
-        );
+        self.indices.clear();
+        self.sprite_instances.clear();
+        self.glyph_instances.clear();
+        self.mesh_instances.clear();
 
         self.gpu_data.mask_atlas.clear_in_use();
         self.gpu_data.color_atlas.clear_in_use();
+        self.gpu_data.sdf_atlas.clear_in_use();
 
         self.buffer_cache.retain(|_, v| v.in_use);
         for v in self.buffer_cache.values_mut() {
@@ -205,6 +272,11 @@ impl Context {
         height: u32,
         screen: bool,
     ) -> CanvasKey {
+        let data = self.create_canvas_data(width, height, screen);
+        self.canvas_datas.insert(data)
+    }
+    /// the gpu resources of a canvas, `screen` canvases render to the surface instead of a texture
+    pub(crate) fn create_canvas_data(&self, width: u32, height: u32, screen: bool) -> CanvasData {
         let globals_buffer =
             self.gpu_data
                 .device
@@ -265,7 +337,7 @@ impl Context {
             .create_texture(&depth_stencil_descriptor)
             .create_view(&wgpu::TextureViewDescriptor::default());
 
-        self.canvas_datas.insert(CanvasData {
+        CanvasData {
             multisample_descriptor,
             output_multisample_view,
             depth_stencil_descriptor,
@@ -284,8 +356,9 @@ impl Context {
             }),
             globals_buffer,
             bind_group_0,
-        })
+        }
     }
+    /// canvases keep their contents between frames until `Canvas::clear` is called
     pub fn create_canvas(&mut self, width: u32, height: u32) -> CanvasKey {
         self.create_canvas_inner(width, height, false)
     }
@@ -360,7 +433,7 @@ impl Context {
         &self.window
     }
     pub fn mouse_pos(&self) -> Vec2 {
-        self.mouse_pos
+        self.window_to_virtual(self.mouse_pos)
     }
     pub fn font_system(&mut self) -> &mut cosmic_text::FontSystem {
         &mut self.gpu_data.font_system
@@ -440,6 +513,10 @@ impl Context {
             })
             .unwrap_or(false)
     }
+    /// shift, control, alt and super keys currently held
+    pub fn modifiers(&self) -> ModifiersState {
+        self.modifiers
+    }
     pub fn mouse_wheel_delta(&self) -> Vec2 {
         if self.run_mode == ContextRunMode::Render
             && Some(self.render_frame) == self.mouse_wheel_info.render_frame
@@ -459,10 +536,21 @@ impl Context {
         height: u32,
         filter: TextureFilter,
     ) -> TextureKey {
+        let texture = self.upload_texture(rgba.to_vec(), width, height, filter);
+        self.loaded_textures.insert(texture)
+    }
+    /// the pixels are kept so the texture can be uploaded again if the device is lost
+    pub(crate) fn upload_texture(
+        &self,
+        rgba: Vec<u8>,
+        width: u32,
+        height: u32,
+        filter: TextureFilter,
+    ) -> LoadedTexture {
         let texture = TextureBundle::from_rgba(
             &self.gpu_data.device,
             &self.gpu_data.queue,
-            rgba,
+            &rgba,
             width,
             height,
             match filter {
@@ -480,10 +568,12 @@ impl Context {
                 },
             ),
         );
-        self.loaded_textures.insert(LoadedTexture {
+        LoadedTexture {
             texture,
             bind_group,
-        })
+            pixels: rgba,
+            filter,
+        }
     }
     pub fn load_texture_path<P: AsRef<Path>>(
         &mut self,
@@ -530,7 +620,14 @@ impl Context {
             .unwrap()
     }
 
-    pub(crate) fn render(&self, output: SurfaceTexture) {
+    pub(crate) fn render(&mut self, output: SurfaceTexture) {
+        let submit_start = Instant::now();
+        if let Some(timer) = &self.gpu_data.timer {
+            if let Some(gpu_time) = timer.poll(&self.gpu_data.device) {
+                self.last_gpu_time = Some(gpu_time);
+            }
+        }
+
         // let Ok(output) = self.gpu_data.surface.get_current_texture() else {
         //     return;
         // };
@@ -543,130 +640,385 @@ impl Context {
             .device
             .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
 
-        let vertex_buffer =
-            self.gpu_data
-                .device
-                .create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                    label: Some("Vertex Buffer"),
-                    contents: bytemuck::cast_slice(&self.vertices),
-                    usage: wgpu::BufferUsages::VERTEX,
-                });
+        self.gpu_data.upload_geometry(
+            &self.vertices,
+            &self.indices,
+            &self.sprite_instances,
+            &self.glyph_instances,
+            &self.mesh_instances,
+        );
 
-        if !self.vertices.is_empty() {
-            let num_vertices = self.vertices.len() as u32;
-            for (idx, pass) in self.passes.iter().enumerate() {
-                let render_pass_start_vertex = pass.calls[0].start_vertex;
-                let render_pass_end_vertex = self
-                    .passes
-                    .get(idx + 1)
-                    .map(|p| p.calls[0].start_vertex)
-                    .unwrap_or(num_vertices);
-
-                if render_pass_end_vertex - render_pass_start_vertex == 0 {
-                    continue;
-                }
+        let stats = &mut self.current_stats;
+        stats.vertices = self.vertices.len() as u32;
+        stats.indices = self.indices.len() as u32;
+        stats.sprite_instances = self.sprite_instances.len() as u32;
+        stats.glyph_instances = self.glyph_instances.len() as u32;
 
-                {
-                    let pass_desc = wgpu::RenderPassDescriptor {
-                        label: Some("Render Pass"),
-                        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                            view: &self.canvas_datas[pass.target_canvas].output_multisample_view,
-                            resolve_target: Some(
-                                if let Some(tex) =
-                                    &self.canvas_datas[pass.target_canvas].output_texture
-                                {
-                                    &tex.view
-                                } else {
-                                    &output_view
-                                },
-                            ),
-                            ops: wgpu::Operations {
-                                load: wgpu::LoadOp::Load,
-                                store: wgpu::StoreOp::Store,
+        let num_indices = self.indices.len() as u32;
+        let pass_ranges = (0..self.passes.len())
+            .map(|idx| {
+                self.passes[idx].calls[0].start_index
+                    ..self
+                        .passes
+                        .get(idx + 1)
+                        .map(|p| p.calls[0].start_index)
+                        .unwrap_or(num_indices)
+            })
+            .collect::<Vec<_>>();
+        let drawn_passes = self
+            .passes
+            .iter()
+            .zip(&pass_ranges)
+            .enumerate()
+            .filter(|(_, (pass, range))| {
+                !range.is_empty()
+                    || pass.clear.is_some()
+                    || pass.calls.iter().any(|c| {
+                        matches!(
+                            c.typ,
+                            DrawCallType::Sprites { .. }
+                                | DrawCallType::Glyphs { .. }
+                                | DrawCallType::Mesh { .. }
+                        )
+                    })
+            })
+            .map(|(idx, _)| idx)
+            .collect::<Vec<_>>();
+
+        for &idx in &drawn_passes {
+            let pass = &self.passes[idx];
+            let render_pass_end_index = pass_ranges[idx].end;
+            stats.render_passes += 1;
+
+            {
+                let pass_desc = wgpu::RenderPassDescriptor {
+                    label: Some("Render Pass"),
+                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                        view: &self.canvas_datas[pass.target_canvas].output_multisample_view,
+                        resolve_target: Some(
+                            if let Some(tex) = &self.canvas_datas[pass.target_canvas].output_texture
+                            {
+                                &tex.view
+                            } else {
+                                &output_view
+                            },
+                        ),
+                        ops: wgpu::Operations {
+                            load: match pass.clear {
+                                Some(color) => wgpu::LoadOp::Clear(wgpu::Color {
+                                    r: color.r as f64,
+                                    g: color.g as f64,
+                                    b: color.b as f64,
+                                    a: color.a as f64,
+                                }),
+                                None => wgpu::LoadOp::Load,
                             },
-                            depth_slice: None,
-                        })],
-                        depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
-                            view: &self.canvas_datas[pass.target_canvas].depth_stencil_view,
-                            depth_ops: None,
-                            stencil_ops: Some(wgpu::Operations {
-                                load: wgpu::LoadOp::Load,
-                                store: wgpu::StoreOp::Store,
-                            }),
+                            store: wgpu::StoreOp::Store,
+                        },
+                        depth_slice: None,
+                    })],
+                    depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                        view: &self.canvas_datas[pass.target_canvas].depth_stencil_view,
+                        depth_ops: None,
+                        stencil_ops: Some(wgpu::Operations {
+                            load: match pass.clear {
+                                Some(_) => wgpu::LoadOp::Clear(0),
+                                None => wgpu::LoadOp::Load,
+                            },
+                            store: wgpu::StoreOp::Store,
                         }),
-                        occlusion_query_set: None,
-                        timestamp_writes: None,
-                    };
-                    let mut render_pass = encoder.begin_render_pass(&pass_desc);
-
-                    render_pass.set_bind_group(
-                        0,
-                        self.canvas_datas[pass.target_canvas]
-                            .bind_group_0
-                            .get_bind_group(),
-                        &[],
-                    );
-                    render_pass.set_bind_group(
-                        1,
-                        self.gpu_data.dummy_texture_bind.get_bind_group(),
-                        &[],
-                    );
-                    render_pass.set_bind_group(
-                        2,
-                        self.gpu_data.text_atlas_bind_group.get_bind_group(),
-                        &[],
-                    );
-
-                    render_pass.set_vertex_buffer(0, vertex_buffer.slice(..));
-
-                    for (idx, call) in pass.calls.iter().enumerate() {
-                        let call_end_vertex = pass
-                            .calls
-                            .get(idx + 1)
-                            .map(|c| c.start_vertex)
-                            .unwrap_or(render_pass_end_vertex);
-
-                        match call.typ {
-                            DrawCallType::Draw {
-                                blend_mode,
-                                set_texture,
-                                reference,
-                                end_clip_reference,
-                            } => {
-                                if let Some(end_reference) = end_clip_reference {
-                                    render_pass.set_pipeline(&self.gpu_data.end_clip_pipeline);
-                                    render_pass.set_stencil_reference(end_reference);
-                                    render_pass.draw(0..3, 0..1);
-                                }
-                                if let Some(tex) = set_texture {
-                                    render_pass.set_bind_group(
-                                        1,
-                                        self.loaded_textures[tex].bind_group.get_bind_group(),
-                                        &[],
-                                    );
-                                }
-                                render_pass.set_pipeline(match blend_mode {
+                    }),
+                    occlusion_query_set: None,
+                    timestamp_writes: self.gpu_data.timer.as_ref().and_then(|timer| {
+                        timer.timestamp_writes(
+                            Some(&idx) == drawn_passes.first(),
+                            Some(&idx) == drawn_passes.last(),
+                        )
+                    }),
+                };
+                let mut render_pass = encoder.begin_render_pass(&pass_desc);
+
+                render_pass.set_bind_group(
+                    0,
+                    self.canvas_datas[pass.target_canvas]
+                        .bind_group_0
+                        .get_bind_group(),
+                    &[],
+                );
+                render_pass.set_bind_group(
+                    1,
+                    self.gpu_data.dummy_texture_bind.get_bind_group(),
+                    &[],
+                );
+                render_pass.set_bind_group(
+                    2,
+                    self.gpu_data.text_atlas_bind_group.get_bind_group(),
+                    &[],
+                );
+
+                render_pass.set_vertex_buffer(0, self.gpu_data.vertex_buffer.slice(..));
+                render_pass.set_index_buffer(
+                    self.gpu_data.index_buffer.slice(..),
+                    wgpu::IndexFormat::Uint32,
+                );
+                // `None` while group 1 holds something other than a canvas texture or the dummy
+                let mut bound_texture = Some(None);
+                let mut bound_pipeline = None;
+                stats.bind_group_switches += 3;
+
+                for (idx, call) in pass.calls.iter().enumerate() {
+                    let call_end_index = pass
+                        .calls
+                        .get(idx + 1)
+                        .map(|c| c.start_index)
+                        .unwrap_or(render_pass_end_index);
+
+                    match call.typ {
+                        DrawCallType::Draw {
+                            blend_mode,
+                            texture,
+                            reference,
+                            end_clip_reference,
+                            ..
+                        } => {
+                            if let Some(end_reference) = end_clip_reference {
+                                set_pipeline(
+                                    &mut render_pass,
+                                    &mut bound_pipeline,
+                                    stats,
+                                    &self.gpu_data.end_clip_pipeline,
+                                );
+                                render_pass.set_stencil_reference(end_reference);
+                                stats.draw_calls += 1;
+                                render_pass.draw(0..3, 0..1);
+                            }
+                            if Some(texture) != bound_texture {
+                                stats.bind_group_switches += 1;
+                                render_pass.set_bind_group(
+                                    1,
+                                    match texture {
+                                        Some(tex) => {
+                                            self.loaded_textures[tex].bind_group.get_bind_group()
+                                        }
+                                        None => self.gpu_data.dummy_texture_bind.get_bind_group(),
+                                    },
+                                    &[],
+                                );
+                                bound_texture = Some(texture);
+                            }
+                            set_pipeline(
+                                &mut render_pass,
+                                &mut bound_pipeline,
+                                stats,
+                                match blend_mode {
                                     BlendMode::Normal => &self.gpu_data.draw_normal_pipeline,
                                     BlendMode::Additive => &self.gpu_data.draw_additive_pipeline,
-                                });
-                                render_pass.set_stencil_reference(reference);
-                                render_pass.draw(call.start_vertex..call_end_vertex, 0..1);
+                                },
+                            );
+                            render_pass.set_stencil_reference(reference);
+                            stats.draw_calls += 1;
+                            render_pass.draw_indexed(call.start_index..call_end_index, 0, 0..1);
+                        }
+                        DrawCallType::ClipStart { reference } => {
+                            set_pipeline(
+                                &mut render_pass,
+                                &mut bound_pipeline,
+                                stats,
+                                &self.gpu_data.start_clip_pipeline,
+                            );
+                            render_pass.set_stencil_reference(reference);
+                            stats.draw_calls += 1;
+                            render_pass.draw_indexed(call.start_index..call_end_index, 0, 0..1);
+                        }
+                        DrawCallType::CompositeVirtual => {
+                            let Some(v) = &self.virtual_resolution else {
+                                continue;
+                            };
+                            stats.bind_group_switches += 1;
+                            render_pass.set_bind_group(1, v.bind_group.get_bind_group(), &[]);
+                            bound_texture = None;
+                            set_pipeline(
+                                &mut render_pass,
+                                &mut bound_pipeline,
+                                stats,
+                                &self.gpu_data.draw_normal_pipeline,
+                            );
+                            render_pass.set_stencil_reference(0);
+                            stats.draw_calls += 1;
+                            render_pass.draw_indexed(call.start_index..call_end_index, 0, 0..1);
+                        }
+                        DrawCallType::Sprites {
+                            blend_mode,
+                            texture,
+                            reference,
+                            first_instance,
+                            instance_count,
+                            ..
+                        } => {
+                            if Some(Some(texture)) != bound_texture {
+                                stats.bind_group_switches += 1;
+                                render_pass.set_bind_group(
+                                    1,
+                                    self.loaded_textures[texture].bind_group.get_bind_group(),
+                                    &[],
+                                );
+                                bound_texture = Some(Some(texture));
                             }
-                            DrawCallType::ClipStart { reference } => {
-                                render_pass.set_pipeline(&self.gpu_data.start_clip_pipeline);
-                                render_pass.set_stencil_reference(reference);
-                                render_pass.draw(call.start_vertex..call_end_vertex, 0..1);
+                            set_pipeline(
+                                &mut render_pass,
+                                &mut bound_pipeline,
+                                stats,
+                                match blend_mode {
+                                    BlendMode::Normal => &self.gpu_data.sprite_normal_pipeline,
+                                    BlendMode::Additive => &self.gpu_data.sprite_additive_pipeline,
+                                },
+                            );
+                            render_pass.set_stencil_reference(reference);
+                            render_pass
+                                .set_vertex_buffer(0, self.gpu_data.instance_buffer.slice(..));
+                            stats.draw_calls += 1;
+                            render_pass.draw(0..4, first_instance..first_instance + instance_count);
+                            render_pass.set_vertex_buffer(0, self.gpu_data.vertex_buffer.slice(..));
+                        }
+                        DrawCallType::Glyphs {
+                            blend_mode,
+                            reference,
+                            first_instance,
+                            instance_count,
+                            ..
+                        } => {
+                            stats.bind_group_switches += 1;
+                            render_pass.set_bind_group(
+                                1,
+                                self.gpu_data.glyph_atlas_bind_group.get_bind_group(),
+                                &[],
+                            );
+                            bound_texture = None;
+                            set_pipeline(
+                                &mut render_pass,
+                                &mut bound_pipeline,
+                                stats,
+                                match blend_mode {
+                                    BlendMode::Normal => &self.gpu_data.glyph_normal_pipeline,
+                                    BlendMode::Additive => &self.gpu_data.glyph_additive_pipeline,
+                                },
+                            );
+                            render_pass.set_stencil_reference(reference);
+                            render_pass.set_vertex_buffer(
+                                0,
+                                self.gpu_data.glyph_instance_buffer.slice(..),
+                            );
+                            stats.draw_calls += 1;
+                            render_pass.draw(0..4, first_instance..first_instance + instance_count);
+                            render_pass.set_vertex_buffer(0, self.gpu_data.vertex_buffer.slice(..));
+                        }
+                        DrawCallType::Mesh {
+                            blend_mode,
+                            texture,
+                            reference,
+                            mesh,
+                            instance,
+                            ..
+                        } => {
+                            // wgpu can't bind the empty buffers of a mesh without geometry
+                            let Some(mesh) = self
+                                .meshes
+                                .get(mesh)
+                                .filter(|m| m.index_count > 0 && !m.vertices.is_empty())
+                            else {
+                                continue;
+                            };
+                            if Some(texture) != bound_texture {
+                                stats.bind_group_switches += 1;
+                                render_pass.set_bind_group(
+                                    1,
+                                    match texture {
+                                        Some(tex) => {
+                                            self.loaded_textures[tex].bind_group.get_bind_group()
+                                        }
+                                        None => self.gpu_data.dummy_texture_bind.get_bind_group(),
+                                    },
+                                    &[],
+                                );
+                                bound_texture = Some(texture);
                             }
+                            set_pipeline(
+                                &mut render_pass,
+                                &mut bound_pipeline,
+                                stats,
+                                match blend_mode {
+                                    BlendMode::Normal => &self.gpu_data.mesh_normal_pipeline,
+                                    BlendMode::Additive => &self.gpu_data.mesh_additive_pipeline,
+                                },
+                            );
+                            render_pass.set_stencil_reference(reference);
+                            render_pass.set_vertex_buffer(0, mesh.vertex_buffer.slice(..));
+                            render_pass
+                                .set_vertex_buffer(1, self.gpu_data.mesh_instance_buffer.slice(..));
+                            render_pass.set_index_buffer(
+                                mesh.index_buffer.slice(..),
+                                wgpu::IndexFormat::Uint32,
+                            );
+                            stats.draw_calls += 1;
+                            render_pass.draw_indexed(
+                                0..mesh.index_count,
+                                0,
+                                instance..instance + 1,
+                            );
+                            render_pass.set_vertex_buffer(0, self.gpu_data.vertex_buffer.slice(..));
+                            render_pass.set_index_buffer(
+                                self.gpu_data.index_buffer.slice(..),
+                                wgpu::IndexFormat::Uint32,
+                            );
                         }
                     }
                 }
             }
         }
 
+        if let Some(timer) = &self.gpu_data.timer
+            && !drawn_passes.is_empty()
+        {
+            timer.resolve(&mut encoder);
+        }
+
         self.gpu_data.queue.submit([encoder.finish()]);
+        if let Some(timer) = &self.gpu_data.timer {
+            timer.map();
+        }
         output.present();
+
+        let stats = &mut self.current_stats;
+        stats.texture_memory_bytes = self
+            .loaded_textures
+            .values()
+            .map(|t| {
+                let t = &t.texture.texture;
+                t.width() as u64 * t.height() as u64 * 4
+            })
+            .sum();
+        stats.mask_atlas = AtlasStats::of(&self.gpu_data.mask_atlas);
+        stats.color_atlas = AtlasStats::of(&self.gpu_data.color_atlas);
+        stats.sdf_atlas = AtlasStats::of(&self.gpu_data.sdf_atlas);
+        stats.submit_time = submit_start.elapsed();
+        stats.gpu_time = self.last_gpu_time;
+        self.frame_stats = std::mem::take(&mut self.current_stats);
     }
 }
+/// sets the pipeline unless it is already bound, counting the switch
+fn set_pipeline<'p>(
+    render_pass: &mut wgpu::RenderPass<'_>,
+    bound: &mut Option<&'p wgpu::RenderPipeline>,
+    stats: &mut FrameStats,
+    pipeline: &'p wgpu::RenderPipeline,
+) {
+    if !bound.is_some_and(|b| std::ptr::eq(b, pipeline)) {
+        render_pass.set_pipeline(pipeline);
+        *bound = Some(pipeline);
+        stats.pipeline_switches += 1;
+    }
+}
+
 impl<'a> CanvasContext<'a> {
     pub fn draw_canvas<F, R>(&mut self, key: CanvasKey, cb: F) -> R
     where
@@ -676,24 +1028,28 @@ impl<'a> CanvasContext<'a> {
             let DrawCallType::Draw {
                 reference,
                 blend_mode,
+                texture,
+                layer,
                 ..
             } = self.inner.passes.last().unwrap().calls.last().unwrap().typ
             else {
                 panic!("started sub-canvas draw during clip draw")
             };
-            (canvas, reference, blend_mode)
+            (canvas, reference, blend_mode, texture, layer)
         });
 
         self.inner.current_canvas = Some(key);
         self.inner.passes.push(RenderPass {
             target_canvas: key,
+            clear: None,
             calls: vec![DrawCall {
-                start_vertex: self.inner.vertices.len() as u32,
+                start_index: self.inner.indices.len() as u32,
                 typ: DrawCallType::Draw {
                     blend_mode: BlendMode::Normal,
-                    set_texture: None,
+                    texture: None,
                     reference: 0,
                     end_clip_reference: None,
+                    layer: 0,
                 },
             }],
         });
@@ -702,16 +1058,19 @@ impl<'a> CanvasContext<'a> {
 
         let r = cb(&mut canvas);
 
-        if let Some((prev_canvas, prev_reference, prev_blend_mode)) = prev {
+        if let Some((prev_canvas, prev_reference, prev_blend_mode, prev_texture, prev_layer)) = prev
+        {
             self.inner.passes.push(RenderPass {
                 target_canvas: prev_canvas,
+                clear: None,
                 calls: vec![DrawCall {
-                    start_vertex: self.inner.vertices.len() as u32,
+                    start_index: self.inner.indices.len() as u32,
                     typ: DrawCallType::Draw {
                         blend_mode: prev_blend_mode,
-                        set_texture: None,
+                        texture: prev_texture,
                         reference: prev_reference,
                         end_clip_reference: None,
+                        layer: prev_layer,
                     },
                 }],
             });
@@ -0,0 +1,57 @@
+use std::mem;
+
+use crate::{context::Context, error::MapleError, render::GPUData};
+
+impl Context {
+    /// replaces the lost device with a new one and recreates everything that lived on it.
+    /// textures and meshes are uploaded again from their cpu copies, canvases come back blank
+    pub(crate) fn recover_device(&mut self) -> Result<(), MapleError> {
+        let mut gpu_data = pollster::block_on(GPUData::with_surface(
+            self.gpu_data.instance.clone(),
+            self.gpu_data.surface.clone(),
+            self.gpu_data.surface_config.present_mode,
+        ))?;
+        gpu_data.resize(
+            self.gpu_data.surface_config.width,
+            self.gpu_data.surface_config.height,
+        );
+        // loaded fonts live on the cpu, only the glyph atlases have to be refilled
+        mem::swap(&mut gpu_data.font_system, &mut self.gpu_data.font_system);
+        self.gpu_data = gpu_data;
+
+        let keys = self.canvas_datas.keys().collect::<Vec<_>>();
+        for key in keys {
+            let canvas = &self.canvas_datas[key];
+            let size = canvas.multisample_descriptor.size;
+            let screen = canvas.output_texture.is_none();
+            self.canvas_datas[key] = self.create_canvas_data(size.width, size.height, screen);
+        }
+
+        let keys = self.loaded_textures.keys().collect::<Vec<_>>();
+        for key in keys {
+            let texture = &mut self.loaded_textures[key];
+            let (width, height) = (
+                texture.texture.texture.width(),
+                texture.texture.texture.height(),
+            );
+            let (pixels, filter) = (mem::take(&mut texture.pixels), texture.filter);
+            self.loaded_textures[key] = self.upload_texture(pixels, width, height, filter);
+        }
+
+        let keys = self.meshes.keys().collect::<Vec<_>>();
+        for key in keys {
+            let mesh = &mut self.meshes[key];
+            let (vertices, indices) = (mem::take(&mut mesh.vertices), mem::take(&mut mesh.indices));
+            self.meshes[key] = self.upload_mesh(vertices, indices);
+        }
+
+        // sets up the virtual canvas and its bind group again with the same settings
+        if let Some(v) = &self.virtual_resolution {
+            let (size, mode, filter) = (v.size, v.mode, v.filter);
+            self.set_virtual_resolution(size.x, size.y, mode, filter);
+        }
+
+        self.last_gpu_time = None;
+        Ok(())
+    }
+}
@@ -0,0 +1,62 @@
+use std::time::Duration;
+
+use crate::{context::Context, render::text::atlas::GlyphAtlas};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct AtlasStats {
+    /// width and height of the atlas texture in pixels
+    pub size: u32,
+    pub glyphs: u32,
+    /// fraction of the atlas area that is allocated to glyphs
+    pub usage: f32,
+    pub memory_bytes: u64,
+}
+
+impl AtlasStats {
+    pub(crate) fn of(atlas: &GlyphAtlas) -> Self {
+        let area = atlas.texture_size as u64 * atlas.texture_size as u64;
+        Self {
+            size: atlas.texture_size,
+            glyphs: atlas.glyph_cache.len() as u32,
+            usage: atlas.packer.allocated_space() as f32 / area as f32,
+            memory_bytes: area * atlas.channel_count() as u64,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FrameStats {
+    pub vertices: u32,
+    pub indices: u32,
+    pub sprite_instances: u32,
+    pub glyph_instances: u32,
+    pub draw_calls: u32,
+    pub render_passes: u32,
+    pub pipeline_switches: u32,
+    pub bind_group_switches: u32,
+
+    /// memory of the textures loaded through `Context`, excluding canvases and atlases
+    pub texture_memory_bytes: u64,
+    pub mask_atlas: AtlasStats,
+    pub color_atlas: AtlasStats,
+    pub sdf_atlas: AtlasStats,
+    /// text buffer lookups that reused an already shaped buffer
+    pub text_cache_hits: u32,
+    /// text buffer lookups that had to shape a new buffer
+    pub text_cache_misses: u32,
+
+    /// time spent in `AppState::draw`
+    pub draw_time: Duration,
+    /// time spent uploading geometry, encoding the passes and submitting them
+    pub submit_time: Duration,
+    /// gpu time from the start of the first render pass to the end of the last one,
+    /// `None` if the adapter doesn't support timestamp queries. lags a frame or two behind
+    pub gpu_time: Option<Duration>,
+}
+
+impl Context {
+    /// counters and timings of the last rendered frame
+    pub fn frame_stats(&self) -> FrameStats {
+        self.frame_stats
+    }
+}
@@ -15,6 +15,9 @@ pub enum TextureFilter {
 pub struct LoadedTexture {
     pub(crate) texture: TextureBundle,
     pub(crate) bind_group: wgsl_draw::globals::BindGroup1,
+    /// cpu copy of the rgba pixels, used to restore the texture after a device loss
+    pub(crate) pixels: Vec<u8>,
+    pub(crate) filter: TextureFilter,
 }
 
 pub type TextureMap = SlotMap<TextureKey, LoadedTexture>;
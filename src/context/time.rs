@@ -0,0 +1,206 @@
+use std::time::Instant;
+
+use crate::context::Context;
+
+/// the fixed update accumulator counts in nanoseconds multiplied by the tick rate,
+/// so a tick is due every `NANOS_PER_SEC` units and no rounding error builds up
+const NANOS_PER_SEC: u128 = 1_000_000_000;
+
+pub(crate) struct FrameTime {
+    pub(crate) start: Instant,
+    pub(crate) last_frame: Option<Instant>,
+    pub(crate) delta: f32,
+    pub(crate) fixed_alpha: f32,
+
+    pub(crate) fixed_rate: u32,
+    pub(crate) fixed_last_advance: Instant,
+    pub(crate) fixed_accumulator: u128,
+    pub(crate) fixed_max_catch_up: u32,
+    pub(crate) fixed_ticks_this_run: u32,
+    pub(crate) fixed_paused: bool,
+    pub(crate) fixed_pending_steps: u32,
+}
+
+impl FrameTime {
+    pub(crate) fn new(fixed_rate: u32) -> Self {
+        let now = Instant::now();
+        Self {
+            start: now,
+            last_frame: None,
+            delta: 0.0,
+            fixed_alpha: 0.0,
+            fixed_rate: fixed_rate.max(1),
+            fixed_last_advance: now,
+            fixed_accumulator: 0,
+            fixed_max_catch_up: 8,
+            fixed_ticks_this_run: 0,
+            fixed_paused: false,
+            fixed_pending_steps: 0,
+        }
+    }
+
+    fn advance_fixed(&mut self, now: Instant) {
+        if !self.fixed_paused {
+            let elapsed = now.duration_since(self.fixed_last_advance).as_nanos();
+            self.fixed_accumulator += elapsed * self.fixed_rate as u128;
+        }
+        self.fixed_last_advance = now;
+        self.fixed_ticks_this_run = 0;
+    }
+    fn take_fixed_tick(&mut self) -> bool {
+        if self.fixed_pending_steps > 0 {
+            self.fixed_pending_steps -= 1;
+            return true;
+        }
+        if self.fixed_paused || self.fixed_accumulator < NANOS_PER_SEC {
+            return false;
+        }
+        if self.fixed_ticks_this_run >= self.fixed_max_catch_up {
+            self.fixed_accumulator %= NANOS_PER_SEC;
+            return false;
+        }
+        self.fixed_accumulator -= NANOS_PER_SEC;
+        self.fixed_ticks_this_run += 1;
+        true
+    }
+}
+
+impl Context {
+    /// seconds between the start of the previous render frame and this one
+    pub fn delta_time(&self) -> f32 {
+        self.time.delta
+    }
+    /// seconds since the app started
+    pub fn elapsed_time(&self) -> f64 {
+        self.time.start.elapsed().as_secs_f64()
+    }
+    /// how far this render frame is between the last fixed tick and the next one, from 0 to 1.
+    /// interpolate between the previous and current fixed update state with it for smooth motion
+    pub fn fixed_alpha(&self) -> f32 {
+        self.time.fixed_alpha
+    }
+
+    /// fixed updates per second
+    pub fn fixed_update_rate(&self) -> u32 {
+        self.time.fixed_rate
+    }
+    /// changes the fixed updates per second, keeping the progress towards the next tick
+    pub fn set_fixed_update_rate(&mut self, rate: u32) {
+        let rate = rate.max(1);
+        self.time.fixed_accumulator =
+            self.time.fixed_accumulator * rate as u128 / self.time.fixed_rate as u128;
+        self.time.fixed_rate = rate;
+    }
+    /// the most fixed ticks run back to back when the app falls behind, the rest of the
+    /// backlog is dropped. 8 by default
+    pub fn set_max_fixed_catch_up(&mut self, ticks: u32) {
+        self.time.fixed_max_catch_up = ticks.max(1);
+    }
+    /// stops fixed ticks from running until `resume_fixed_update`, time spent paused isn't caught up
+    pub fn pause_fixed_update(&mut self) {
+        self.time.fixed_paused = true;
+    }
+    pub fn resume_fixed_update(&mut self) {
+        self.time.fixed_paused = false;
+    }
+    pub fn is_fixed_update_paused(&self) -> bool {
+        self.time.fixed_paused
+    }
+    /// runs exactly one fixed tick as soon as possible, even while paused
+    pub fn step_fixed_update(&mut self) {
+        self.time.fixed_pending_steps += 1;
+    }
+
+    pub(crate) fn advance_frame_time(&mut self) {
+        let now = Instant::now();
+        self.time.delta = self
+            .time
+            .last_frame
+            .map(|last| now.duration_since(last).as_secs_f32())
+            .unwrap_or(0.0);
+        self.time.last_frame = Some(now);
+
+        self.time.fixed_alpha =
+            (self.time.fixed_accumulator as f64 / NANOS_PER_SEC as f64).clamp(0.0, 1.0) as f32;
+    }
+    /// adds the time since the last call to the fixed update accumulator
+    pub(crate) fn advance_fixed_time(&mut self) {
+        self.time.advance_fixed(Instant::now());
+    }
+    /// whether another fixed tick is due, consuming it from the accumulator
+    pub(crate) fn take_fixed_tick(&mut self) -> bool {
+        self.time.take_fixed_tick()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    /// advances by `ms` and runs every due tick like the app loop does
+    fn frame(time: &mut FrameTime, now: &mut Instant, ms: u64) -> u32 {
+        *now += Duration::from_millis(ms);
+        time.advance_fixed(*now);
+        let mut ticks = 0;
+        while time.take_fixed_tick() {
+            ticks += 1;
+        }
+        ticks
+    }
+
+    fn setup(rate: u32) -> (FrameTime, Instant) {
+        let time = FrameTime::new(rate);
+        let now = time.fixed_last_advance;
+        (time, now)
+    }
+
+    #[test]
+    fn ticks_across_uneven_frames() {
+        let (mut time, mut now) = setup(60);
+        // a tick is due every 16.67ms
+        let ticks = [10, 10, 20, 5, 30].map(|ms| frame(&mut time, &mut now, ms));
+        assert_eq!(ticks, [0, 1, 1, 0, 2]);
+    }
+
+    #[test]
+    fn no_drift_over_a_second() {
+        let (mut time, mut now) = setup(60);
+        let mut ticks = 0;
+        for _ in 0..20 {
+            for ms in [7, 13, 3, 25, 2] {
+                ticks += frame(&mut time, &mut now, ms);
+            }
+        }
+        assert_eq!(ticks, 60);
+        assert_eq!(time.fixed_accumulator, 0);
+    }
+
+    #[test]
+    fn catch_up_is_clamped() {
+        let (mut time, mut now) = setup(60);
+        assert_eq!(frame(&mut time, &mut now, 1000), 8);
+        // the rest of the backlog is dropped, only the progress towards the next tick is kept
+        assert!(time.fixed_accumulator < NANOS_PER_SEC);
+        assert_eq!(frame(&mut time, &mut now, 0), 0);
+
+        time.fixed_max_catch_up = 2;
+        assert_eq!(frame(&mut time, &mut now, 100), 2);
+    }
+
+    #[test]
+    fn pause_and_step() {
+        let (mut time, mut now) = setup(60);
+        time.fixed_paused = true;
+        assert_eq!(frame(&mut time, &mut now, 1000), 0);
+
+        time.fixed_pending_steps += 1;
+        assert_eq!(frame(&mut time, &mut now, 0), 1);
+
+        // time spent paused isn't caught up
+        time.fixed_paused = false;
+        assert_eq!(frame(&mut time, &mut now, 0), 0);
+        assert_eq!(frame(&mut time, &mut now, 20), 1);
+    }
+}
@@ -0,0 +1,178 @@
+use glam::{UVec2, Vec2, uvec2, vec2};
+
+use crate::{
+    canvas::{CanvasKey, QUAD_INDICES, color::Color},
+    context::{Context, DrawCall, DrawCallType, RenderPass, texture::TextureFilter},
+    render::shaders::{wgsl_common, wgsl_draw},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ScalingMode {
+    /// stretches over the whole window, ignoring the aspect ratio
+    Stretch,
+    /// scales as large as possible while keeping the aspect ratio, with bars filling the rest
+    Fit,
+    /// like `Fit` but only scales by whole numbers
+    IntegerScale,
+    /// covers the whole window while keeping the aspect ratio, cropping what doesn't fit
+    FillCrop,
+}
+
+pub struct VirtualResolution {
+    pub(crate) canvas: CanvasKey,
+    pub(crate) size: UVec2,
+    pub(crate) mode: ScalingMode,
+    pub(crate) letterbox_color: Color,
+    pub(crate) filter: TextureFilter,
+    pub(crate) bind_group: wgsl_draw::globals::BindGroup1,
+}
+
+impl Context {
+    /// makes the main canvas render at a fixed resolution that gets scaled to the window,
+    /// `mouse_pos` is then reported in that resolution
+    pub fn set_virtual_resolution(
+        &mut self,
+        width: u32,
+        height: u32,
+        mode: ScalingMode,
+        filter: TextureFilter,
+    ) {
+        let letterbox_color = self
+            .virtual_resolution
+            .take()
+            .map(|v| {
+                self.delete_canvas(v.canvas);
+                v.letterbox_color
+            })
+            .unwrap_or(Color::rgb(0.0, 0.0, 0.0));
+
+        let canvas = self.create_canvas(width, height);
+
+        let filter_mode = match filter {
+            TextureFilter::Linear => wgpu::FilterMode::Linear,
+            TextureFilter::Nearest => wgpu::FilterMode::Nearest,
+        };
+        let sampler = self
+            .gpu_data
+            .device
+            .create_sampler(&wgpu::SamplerDescriptor {
+                address_mode_u: wgpu::AddressMode::ClampToEdge,
+                address_mode_v: wgpu::AddressMode::ClampToEdge,
+                address_mode_w: wgpu::AddressMode::ClampToEdge,
+                mag_filter: filter_mode,
+                min_filter: filter_mode,
+                mipmap_filter: filter_mode,
+                ..Default::default()
+            });
+        let bind_group = wgsl_draw::globals::BindGroup1::from_bindings(
+            &self.gpu_data.device,
+            wgsl_draw::globals::BindGroup1Entries::new(
+                wgsl_draw::globals::BindGroup1EntriesEntriesParams {
+                    TEXTURE_T: &self.canvas_datas[canvas]
+                        .output_texture
+                        .as_ref()
+                        .unwrap()
+                        .view,
+                    TEXTURE_S: &sampler,
+                },
+            ),
+        );
+
+        self.virtual_resolution = Some(VirtualResolution {
+            canvas,
+            size: uvec2(width, height),
+            mode,
+            letterbox_color,
+            filter,
+            bind_group,
+        });
+    }
+    /// goes back to rendering the main canvas at the window resolution
+    pub fn clear_virtual_resolution(&mut self) {
+        if let Some(v) = self.virtual_resolution.take() {
+            self.delete_canvas(v.canvas);
+        }
+    }
+    pub fn virtual_resolution(&self) -> Option<UVec2> {
+        self.virtual_resolution.as_ref().map(|v| v.size)
+    }
+    /// color of the bars left around the virtual resolution by some scaling modes
+    pub fn set_letterbox_color(&mut self, color: Color) {
+        if let Some(v) = &mut self.virtual_resolution {
+            v.letterbox_color = color;
+        }
+    }
+    /// the (position, size) in window pixels that the virtual resolution is displayed at
+    pub fn virtual_viewport(&self) -> Option<(Vec2, Vec2)> {
+        let v = self.virtual_resolution.as_ref()?;
+
+        let window = vec2(
+            self.gpu_data.surface_config.width as f32,
+            self.gpu_data.surface_config.height as f32,
+        );
+        let size = v.size.as_vec2();
+        let scale = window / size;
+
+        let scaled = match v.mode {
+            ScalingMode::Stretch => window,
+            ScalingMode::Fit => size * scale.min_element(),
+            ScalingMode::IntegerScale => size * scale.min_element().floor().max(1.0),
+            ScalingMode::FillCrop => size * scale.max_element(),
+        };
+
+        Some(((window - scaled) / 2.0, scaled))
+    }
+    /// the mouse position in window pixels, ignoring the virtual resolution
+    pub fn window_mouse_pos(&self) -> Vec2 {
+        self.mouse_pos
+    }
+
+    pub(crate) fn window_to_virtual(&self, pos: Vec2) -> Vec2 {
+        match (self.virtual_viewport(), &self.virtual_resolution) {
+            (Some((viewport_pos, viewport_size)), Some(v)) => {
+                (pos - viewport_pos) / viewport_size * v.size.as_vec2()
+            }
+            _ => pos,
+        }
+    }
+    pub(crate) fn virtual_to_window(&self, pos: Vec2) -> Vec2 {
+        match (self.virtual_viewport(), &self.virtual_resolution) {
+            (Some((viewport_pos, viewport_size)), Some(v)) => {
+                viewport_pos + pos / v.size.as_vec2() * viewport_size
+            }
+            _ => pos,
+        }
+    }
+    /// draws the virtual canvas onto `target`, scaled according to the scaling mode
+    pub(crate) fn composite_virtual(&mut self, target: CanvasKey) {
+        let (Some((pos, size)), Some(v)) = (self.virtual_viewport(), &self.virtual_resolution)
+        else {
+            return;
+        };
+        let uv_size = v.size.as_vec2();
+
+        self.passes.push(RenderPass {
+            target_canvas: target,
+            clear: Some(v.letterbox_color),
+            calls: vec![DrawCall {
+                start_index: self.indices.len() as u32,
+                typ: DrawCallType::CompositeVirtual,
+            }],
+        });
+
+        let a = (pos, vec2(0.0, 0.0));
+        let b = (pos + vec2(size.x, 0.0), vec2(uv_size.x, 0.0));
+        let c = (pos + size, uv_size);
+        let d = (pos + vec2(0.0, size.y), vec2(0.0, uv_size.y));
+        let base = self.vertices.len() as u32;
+        self.indices.extend(QUAD_INDICES.map(|i| base + i));
+        self.vertices.extend([a, b, c, d].map(|(pos, uv)| {
+            wgsl_common::structs::VertexInput::new(
+                pos.to_array(),
+                [1.0; 4],
+                uv.to_array(),
+                [-1.0, 0.0],
+            )
+        }));
+    }
+}
@@ -0,0 +1,145 @@
+use glam::{UVec2, Vec2, uvec2};
+use winit::{
+    dpi::{PhysicalPosition, PhysicalSize},
+    window::{CursorGrabMode, Fullscreen},
+};
+
+use crate::context::Context;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FullscreenMode {
+    Windowed,
+    /// a borderless window covering the current monitor
+    Borderless,
+    /// takes over the current monitor at its highest resolution and refresh rate,
+    /// falls back to `Borderless` if the monitor reports no video modes
+    Exclusive,
+}
+
+impl Context {
+    /// closes the window and returns from `run_app` once the current frame is done
+    pub fn exit(&mut self) {
+        self.exit_requested = true;
+    }
+
+    pub fn set_title(&mut self, title: &str) {
+        self.window.set_title(title);
+    }
+    /// size of the window's drawable area in pixels
+    pub fn window_size(&self) -> UVec2 {
+        let size = self.window.inner_size();
+        uvec2(size.width, size.height)
+    }
+    /// asks for a new window size in pixels, the platform may adjust or ignore it.
+    /// the main canvas follows once the window is actually resized
+    pub fn set_window_size(&mut self, width: u32, height: u32) {
+        _ = self
+            .window
+            .request_inner_size(PhysicalSize::new(width.max(1), height.max(1)));
+    }
+    pub fn set_resizable(&mut self, resizable: bool) {
+        self.window.set_resizable(resizable);
+    }
+    pub fn fullscreen_mode(&self) -> FullscreenMode {
+        match self.window.fullscreen() {
+            None => FullscreenMode::Windowed,
+            Some(Fullscreen::Borderless(_)) => FullscreenMode::Borderless,
+            Some(Fullscreen::Exclusive(_)) => FullscreenMode::Exclusive,
+        }
+    }
+    pub fn set_fullscreen(&mut self, mode: FullscreenMode) {
+        let fullscreen = match mode {
+            FullscreenMode::Windowed => None,
+            FullscreenMode::Borderless => Some(Fullscreen::Borderless(None)),
+            FullscreenMode::Exclusive => Some(
+                self.window
+                    .current_monitor()
+                    .and_then(|monitor| {
+                        monitor.video_modes().max_by_key(|v| {
+                            (
+                                v.size().width * v.size().height,
+                                v.refresh_rate_millihertz(),
+                            )
+                        })
+                    })
+                    .map(Fullscreen::Exclusive)
+                    .unwrap_or(Fullscreen::Borderless(None)),
+            ),
+        };
+        self.window.set_fullscreen(fullscreen);
+    }
+
+    pub fn set_cursor_visible(&mut self, visible: bool) {
+        self.window.set_cursor_visible(visible);
+    }
+    /// keeps the cursor inside the window (`Confined`) or in place (`Locked`). platforms only
+    /// support one of the two, so the other is tried if the requested one fails.
+    /// returns whether the cursor ended up grabbed
+    pub fn set_cursor_grab(&mut self, mode: CursorGrabMode) -> bool {
+        let fallback = match mode {
+            CursorGrabMode::None => return self.window.set_cursor_grab(mode).is_ok(),
+            CursorGrabMode::Confined => CursorGrabMode::Locked,
+            CursorGrabMode::Locked => CursorGrabMode::Confined,
+        };
+        self.window.set_cursor_grab(mode).is_ok() || self.window.set_cursor_grab(fallback).is_ok()
+    }
+
+    pub fn present_mode(&self) -> wgpu::PresentMode {
+        self.gpu_data.surface_config.present_mode
+    }
+    /// present modes supported by the window's surface
+    pub fn supported_present_modes(&self) -> &[wgpu::PresentMode] {
+        &self.gpu_data.present_modes
+    }
+    /// the surface is reconfigured with the new present mode after the current frame,
+    /// returns false and keeps the current mode if the surface doesn't support it
+    pub fn set_present_mode(&mut self, mode: wgpu::PresentMode) -> bool {
+        let supported = matches!(
+            mode,
+            wgpu::PresentMode::AutoVsync | wgpu::PresentMode::AutoNoVsync
+        ) || self.gpu_data.present_modes.contains(&mode);
+        if supported {
+            self.gpu_data.surface_config.present_mode = mode;
+            self.gpu_data.surface_outdated = true;
+        }
+        supported
+    }
+    /// picks the best supported present mode with or without vsync
+    pub fn set_vsync(&mut self, vsync: bool) {
+        self.set_present_mode(if vsync {
+            wgpu::PresentMode::AutoVsync
+        } else {
+            wgpu::PresentMode::AutoNoVsync
+        });
+    }
+
+    /// enables the input method editor so composed text (e.g. chinese, japanese, korean) arrives
+    /// through `AppState::ime_preedit` and `AppState::text_input`. off by default
+    pub fn set_ime_allowed(&mut self, allowed: bool) {
+        if allowed == self.ime_allowed {
+            return;
+        }
+        self.ime_allowed = allowed;
+        self.window.set_ime_allowed(allowed);
+        if !allowed {
+            self.ime_composing = false;
+        }
+    }
+    pub fn is_ime_allowed(&self) -> bool {
+        self.ime_allowed
+    }
+    /// the area of the text being edited, in the same coordinates as `mouse_pos`.
+    /// the ime places its candidate window next to it
+    pub fn set_ime_cursor_area(&mut self, pos: Vec2, size: Vec2) {
+        let top_left = self.virtual_to_window(pos);
+        let size = self.virtual_to_window(pos + size) - top_left;
+        self.window.set_ime_cursor_area(
+            PhysicalPosition::new(top_left.x, top_left.y),
+            PhysicalSize::new(size.x, size.y),
+        );
+    }
+    /// whether the ime is currently composing text, keys pressed meanwhile belong to the ime
+    pub fn is_ime_composing(&self) -> bool {
+        self.ime_composing
+    }
+}
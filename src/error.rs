@@ -0,0 +1,48 @@
+use std::fmt;
+
+#[derive(Debug)]
+pub enum MapleError {
+    /// the event loop couldn't be created or exited with an error
+    EventLoop(winit::error::EventLoopError),
+    WindowCreation(winit::error::OsError),
+    SurfaceCreation(wgpu::CreateSurfaceError),
+    /// none of the enabled backends has an adapter that can present to the window
+    NoAdapter(wgpu::RequestAdapterError),
+    /// the adapter reports no formats it can present to the window with
+    UnsupportedSurface,
+    DeviceRequest(wgpu::RequestDeviceError),
+}
+
+impl fmt::Display for MapleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EventLoop(e) => write!(f, "event loop error: {e}"),
+            Self::WindowCreation(e) => write!(f, "failed to create the window: {e}"),
+            Self::SurfaceCreation(e) => write!(f, "failed to create a surface for the window: {e}"),
+            Self::NoAdapter(e) => write!(f, "no suitable graphics adapter found: {e}"),
+            Self::UnsupportedSurface => {
+                write!(f, "the graphics adapter can't present to this window")
+            }
+            Self::DeviceRequest(e) => write!(f, "failed to create the graphics device: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for MapleError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::EventLoop(e) => Some(e),
+            Self::WindowCreation(e) => Some(e),
+            Self::SurfaceCreation(e) => Some(e),
+            Self::NoAdapter(e) => Some(e),
+            Self::UnsupportedSurface => None,
+            Self::DeviceRequest(e) => Some(e),
+        }
+    }
+}
+
+impl From<winit::error::EventLoopError> for MapleError {
+    fn from(value: winit::error::EventLoopError) -> Self {
+        Self::EventLoop(value)
+    }
+}
@@ -4,24 +4,48 @@
 mod app;
 mod canvas;
 mod context;
+mod error;
 mod render;
 mod state;
 
 pub use app::run_app;
 pub use canvas::{
     Canvas, CanvasKey,
+    camera::Camera2D,
     color::Color,
     commands::{
-        ellipse::EllipseBuilder, rect::RectBuilder, text::TextBuilder, texture::TextureBuilder,
+        ellipse::EllipseBuilder,
+        mesh::MeshBuilder,
+        rect::RectBuilder,
+        rich_text::{RichTextBuilder, TextSpan},
+        text::{TextBuilder, VerticalAlign},
+        text_path::{TextOnPathBuilder, TextPath},
+        texture::TextureBuilder,
         triangle::TriangleBuilder,
     },
+    sprite::SpriteInstance,
+    text_input::TextInput,
 };
 pub use context::{
     BlendMode, CanvasContext, Context, EitherKey, TextureBytesLoadError, TexturePathLoadError,
+    fonts::FontLoadError,
+    mesh::{MeshKey, MeshVertex},
+    stats::{AtlasStats, FrameStats},
     texture::{TextureFilter, TextureKey},
+    viewport::ScalingMode,
+    window::FullscreenMode,
 };
+pub use error::MapleError;
 pub use state::AppState;
 
+/// internals used by `benches/geometry_upload.rs`, not part of the api
+#[doc(hidden)]
+pub mod bench {
+    pub use crate::render::{
+        INITIAL_GEOMETRY_BUFFER_SIZE, create_geometry_buffer, write_geometry_buffer,
+    };
+}
+
 pub use cosmic_text;
 pub use glam;
 pub use image;
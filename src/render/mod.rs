@@ -1,43 +1,85 @@
-use std::{mem::offset_of, sync::Arc};
+use std::{
+    mem::offset_of,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+};
 
 use wgpu::util::DeviceExt;
 
-use crate::render::{
-    shaders::{make_fragment_state, make_vertex_state, wgsl_common, wgsl_draw, wgsl_stencil},
-    text::{
-        atlas::{GlyphAtlas, create_atlases_bind_group},
-        glyph::ContentType,
+use crate::{
+    error::MapleError,
+    render::{
+        shaders::{
+            make_fragment_state, make_vertex_state, wgsl_common, wgsl_draw, wgsl_glyph, wgsl_mesh,
+            wgsl_sprite, wgsl_stencil,
+        },
+        text::{
+            atlas::{GlyphAtlas, create_atlases_bind_group, create_glyph_atlases_bind_group},
+            glyph::ContentType,
+        },
+        texture::TextureBundle,
+        timestamps::GpuTimer,
     },
-    texture::TextureBundle,
 };
 
 pub mod shaders;
 pub mod text;
 pub mod texture;
+pub mod timestamps;
 
 pub const SAMPLE_COUNT: u32 = 4;
+/// starting size in bytes of the per frame vertex, index and instance buffers, they grow as needed
+pub const INITIAL_GEOMETRY_BUFFER_SIZE: u64 = 1 << 16;
 
 pub struct GPUData {
+    pub(crate) instance: wgpu::Instance,
     pub(crate) surface: Arc<wgpu::Surface<'static>>,
 
     pub(crate) device: wgpu::Device,
     pub(crate) queue: wgpu::Queue,
     pub(crate) surface_format: wgpu::TextureFormat,
     pub(crate) surface_config: wgpu::SurfaceConfiguration,
+    /// present modes the surface supports with this adapter
+    pub(crate) present_modes: Vec<wgpu::PresentMode>,
+    /// the surface config changed and has to be applied once no surface texture is held
+    pub(crate) surface_outdated: bool,
 
     pub(crate) start_clip_pipeline: wgpu::RenderPipeline,
     pub(crate) end_clip_pipeline: wgpu::RenderPipeline,
     pub(crate) draw_normal_pipeline: wgpu::RenderPipeline,
     pub(crate) draw_additive_pipeline: wgpu::RenderPipeline,
+    pub(crate) sprite_normal_pipeline: wgpu::RenderPipeline,
+    pub(crate) sprite_additive_pipeline: wgpu::RenderPipeline,
+    pub(crate) mesh_normal_pipeline: wgpu::RenderPipeline,
+    pub(crate) mesh_additive_pipeline: wgpu::RenderPipeline,
+    pub(crate) glyph_normal_pipeline: wgpu::RenderPipeline,
+    pub(crate) glyph_additive_pipeline: wgpu::RenderPipeline,
 
     pub(crate) dummy_texture_bind: wgsl_draw::globals::BindGroup1,
 
+    pub(crate) vertex_buffer: wgpu::Buffer,
+    pub(crate) index_buffer: wgpu::Buffer,
+    pub(crate) instance_buffer: wgpu::Buffer,
+    pub(crate) glyph_instance_buffer: wgpu::Buffer,
+    pub(crate) mesh_instance_buffer: wgpu::Buffer,
+
     pub(crate) font_system: cosmic_text::FontSystem,
     pub(crate) swash_cache: cosmic_text::SwashCache,
 
     pub(crate) mask_atlas: GlyphAtlas,
     pub(crate) color_atlas: GlyphAtlas,
+    /// distance field glyphs rendered once at a fixed size, for text drawn with `TextBuilder::sdf`
+    pub(crate) sdf_atlas: GlyphAtlas,
     pub(crate) text_atlas_bind_group: wgsl_draw::globals::BindGroup2,
+    /// the atlases for glyphs drawn with effects or from the sdf atlas
+    pub(crate) glyph_atlas_bind_group: wgsl_glyph::globals::BindGroup1,
+
+    /// only present if the adapter supports timestamp queries
+    pub(crate) timer: Option<GpuTimer>,
+    /// set by wgpu from any thread once the device stops working
+    pub(crate) device_lost: Arc<AtomicBool>,
 }
 
 impl GPUData {
@@ -45,15 +87,26 @@ impl GPUData {
         target: impl Into<wgpu::SurfaceTarget<'static>>,
         backends: wgpu::Backends,
         present_mode: wgpu::PresentMode,
-    ) -> Self {
+    ) -> Result<Self, MapleError> {
         let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor {
             backends,
             flags: wgpu::InstanceFlags::all(),
             ..Default::default()
         });
 
-        let surface = instance.create_surface(target).unwrap();
+        let surface = instance
+            .create_surface(target)
+            .map_err(MapleError::SurfaceCreation)?;
 
+        Self::with_surface(instance, Arc::new(surface), present_mode).await
+    }
+    /// sets up a new adapter and device that present to an existing surface,
+    /// used to start over after the device is lost
+    pub async fn with_surface(
+        instance: wgpu::Instance,
+        surface: Arc<wgpu::Surface<'static>>,
+        present_mode: wgpu::PresentMode,
+    ) -> Result<Self, MapleError> {
         let adapter = instance
             .request_adapter(&wgpu::RequestAdapterOptionsBase {
                 power_preference: wgpu::PowerPreference::None,
@@ -61,12 +114,12 @@ impl GPUData {
                 compatible_surface: Some(&surface),
             })
             .await
-            .unwrap();
+            .map_err(MapleError::NoAdapter)?;
 
         let (device, queue) = adapter
             .request_device(&wgpu::DeviceDescriptor {
                 label: Some("device_descriptor"),
-                required_features: wgpu::Features::empty(),
+                required_features: adapter.features() & wgpu::Features::TIMESTAMP_QUERY,
                 required_limits: wgpu::Limits {
                     ..Default::default()
                 },
@@ -74,9 +127,31 @@ impl GPUData {
                 trace: wgpu::Trace::Off,
             })
             .await
-            .unwrap();
+            .map_err(MapleError::DeviceRequest)?;
+
+        let device_lost = Arc::new(AtomicBool::new(false));
+        device.set_device_lost_callback({
+            let device_lost = device_lost.clone();
+            move |reason, _| {
+                if reason == wgpu::DeviceLostReason::Unknown {
+                    device_lost.store(true, Ordering::Relaxed);
+                }
+            }
+        });
+        // errors are still fatal like wgpu's default handler, except the ones caused by losing the device
+        device.on_uncaptured_error(Box::new({
+            let device_lost = device_lost.clone();
+            move |error| match error {
+                wgpu::Error::OutOfMemory { .. } => device_lost.store(true, Ordering::Relaxed),
+                _ if device_lost.load(Ordering::Relaxed) => (),
+                _ => panic!("wgpu error: {error}\n"),
+            }
+        }));
 
         let surface_caps = surface.get_capabilities(&adapter);
+        if surface_caps.formats.is_empty() || surface_caps.alpha_modes.is_empty() {
+            return Err(MapleError::UnsupportedSurface);
+        }
         let surface_format = surface_caps
             .formats
             .iter()
@@ -159,15 +234,15 @@ impl GPUData {
             let module = wgsl_stencil::create_shader_module(&device);
 
             device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-                label: Some("start_clip_pipeline"),
+                label: Some("end_clip_pipeline"),
                 layout: Some(&wgsl_stencil::create_pipeline_layout(&device)),
                 vertex: make_vertex_state(
                     &module,
-                    &wgsl_stencil::entries::vertex_entry_vs_main(wgpu::VertexStepMode::Vertex),
+                    &wgsl_stencil::entries::vertex_entry_vs_fullscreen(),
                 ),
                 fragment: Some(make_fragment_state(
                     &module,
-                    &wgsl_stencil::entries::fragment_entry_fs_main(&[Some(
+                    &wgsl_stencil::entries::fragment_entry_fs_fullscreen(&[Some(
                         wgpu::ColorTargetState {
                             format: surface_config.format,
                             blend: None,
@@ -310,6 +385,262 @@ impl GPUData {
                     unclipped_depth: false,
                     conservative: false,
                 },
+                depth_stencil: draw_depth_stencil.clone(),
+                multisample: wgpu::MultisampleState {
+                    count: SAMPLE_COUNT,
+                    mask: !0,
+                    alpha_to_coverage_enabled: false,
+                },
+                multiview: None,
+                cache: None,
+            })
+        };
+
+        let sprite_normal_pipeline = {
+            let module = wgsl_sprite::create_shader_module(&device);
+
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some("sprite_normal_pipeline"),
+                layout: Some(&wgsl_sprite::create_pipeline_layout(&device)),
+                vertex: make_vertex_state(
+                    &module,
+                    &wgsl_sprite::entries::vertex_entry_vs_main(wgpu::VertexStepMode::Instance),
+                ),
+                fragment: Some(make_fragment_state(
+                    &module,
+                    &wgsl_sprite::entries::fragment_entry_fs_main(&[Some(
+                        wgpu::ColorTargetState {
+                            format: surface_config.format,
+                            blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                            write_mask: wgpu::ColorWrites::ALL,
+                        },
+                    )]),
+                )),
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleStrip,
+                    strip_index_format: None,
+                    front_face: wgpu::FrontFace::Cw,
+                    cull_mode: None,
+                    polygon_mode: wgpu::PolygonMode::Fill,
+                    unclipped_depth: false,
+                    conservative: false,
+                },
+                depth_stencil: draw_depth_stencil.clone(),
+                multisample: wgpu::MultisampleState {
+                    count: SAMPLE_COUNT,
+                    mask: !0,
+                    alpha_to_coverage_enabled: false,
+                },
+                multiview: None,
+                cache: None,
+            })
+        };
+        let sprite_additive_pipeline = {
+            let module = wgsl_sprite::create_shader_module(&device);
+
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some("sprite_additive_pipeline"),
+                layout: Some(&wgsl_sprite::create_pipeline_layout(&device)),
+                vertex: make_vertex_state(
+                    &module,
+                    &wgsl_sprite::entries::vertex_entry_vs_main(wgpu::VertexStepMode::Instance),
+                ),
+                fragment: Some(make_fragment_state(
+                    &module,
+                    &wgsl_sprite::entries::fragment_entry_fs_main(&[Some(
+                        wgpu::ColorTargetState {
+                            format: surface_config.format,
+                            blend: Some(wgpu::BlendState {
+                                color: wgpu::BlendComponent {
+                                    src_factor: wgpu::BlendFactor::SrcAlpha,
+                                    dst_factor: wgpu::BlendFactor::One,
+                                    operation: wgpu::BlendOperation::Add,
+                                },
+                                alpha: wgpu::BlendComponent::OVER,
+                            }),
+                            write_mask: wgpu::ColorWrites::ALL,
+                        },
+                    )]),
+                )),
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleStrip,
+                    strip_index_format: None,
+                    front_face: wgpu::FrontFace::Cw,
+                    cull_mode: None,
+                    polygon_mode: wgpu::PolygonMode::Fill,
+                    unclipped_depth: false,
+                    conservative: false,
+                },
+                depth_stencil: draw_depth_stencil.clone(),
+                multisample: wgpu::MultisampleState {
+                    count: SAMPLE_COUNT,
+                    mask: !0,
+                    alpha_to_coverage_enabled: false,
+                },
+                multiview: None,
+                cache: None,
+            })
+        };
+
+        let glyph_normal_pipeline = {
+            let module = wgsl_glyph::create_shader_module(&device);
+
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some("glyph_normal_pipeline"),
+                layout: Some(&wgsl_glyph::create_pipeline_layout(&device)),
+                vertex: make_vertex_state(
+                    &module,
+                    &wgsl_glyph::entries::vertex_entry_vs_main(wgpu::VertexStepMode::Instance),
+                ),
+                fragment: Some(make_fragment_state(
+                    &module,
+                    &wgsl_glyph::entries::fragment_entry_fs_main(&[Some(wgpu::ColorTargetState {
+                        format: surface_config.format,
+                        blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })]),
+                )),
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleStrip,
+                    strip_index_format: None,
+                    front_face: wgpu::FrontFace::Cw,
+                    cull_mode: None,
+                    polygon_mode: wgpu::PolygonMode::Fill,
+                    unclipped_depth: false,
+                    conservative: false,
+                },
+                depth_stencil: draw_depth_stencil.clone(),
+                multisample: wgpu::MultisampleState {
+                    count: SAMPLE_COUNT,
+                    mask: !0,
+                    alpha_to_coverage_enabled: false,
+                },
+                multiview: None,
+                cache: None,
+            })
+        };
+        let glyph_additive_pipeline = {
+            let module = wgsl_glyph::create_shader_module(&device);
+
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some("glyph_additive_pipeline"),
+                layout: Some(&wgsl_glyph::create_pipeline_layout(&device)),
+                vertex: make_vertex_state(
+                    &module,
+                    &wgsl_glyph::entries::vertex_entry_vs_main(wgpu::VertexStepMode::Instance),
+                ),
+                fragment: Some(make_fragment_state(
+                    &module,
+                    &wgsl_glyph::entries::fragment_entry_fs_main(&[Some(wgpu::ColorTargetState {
+                        format: surface_config.format,
+                        blend: Some(wgpu::BlendState {
+                            color: wgpu::BlendComponent {
+                                src_factor: wgpu::BlendFactor::SrcAlpha,
+                                dst_factor: wgpu::BlendFactor::One,
+                                operation: wgpu::BlendOperation::Add,
+                            },
+                            alpha: wgpu::BlendComponent::OVER,
+                        }),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })]),
+                )),
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleStrip,
+                    strip_index_format: None,
+                    front_face: wgpu::FrontFace::Cw,
+                    cull_mode: None,
+                    polygon_mode: wgpu::PolygonMode::Fill,
+                    unclipped_depth: false,
+                    conservative: false,
+                },
+                depth_stencil: draw_depth_stencil.clone(),
+                multisample: wgpu::MultisampleState {
+                    count: SAMPLE_COUNT,
+                    mask: !0,
+                    alpha_to_coverage_enabled: false,
+                },
+                multiview: None,
+                cache: None,
+            })
+        };
+
+        let mesh_normal_pipeline = {
+            let module = wgsl_mesh::create_shader_module(&device);
+
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some("mesh_normal_pipeline"),
+                layout: Some(&wgsl_mesh::create_pipeline_layout(&device)),
+                vertex: make_vertex_state(
+                    &module,
+                    &wgsl_mesh::entries::vertex_entry_vs_main(
+                        wgpu::VertexStepMode::Vertex,
+                        wgpu::VertexStepMode::Instance,
+                    ),
+                ),
+                fragment: Some(make_fragment_state(
+                    &module,
+                    &wgsl_mesh::entries::fragment_entry_fs_main(&[Some(wgpu::ColorTargetState {
+                        format: surface_config.format,
+                        blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })]),
+                )),
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleList,
+                    strip_index_format: None,
+                    front_face: wgpu::FrontFace::Cw,
+                    cull_mode: None,
+                    polygon_mode: wgpu::PolygonMode::Fill,
+                    unclipped_depth: false,
+                    conservative: false,
+                },
+                depth_stencil: draw_depth_stencil.clone(),
+                multisample: wgpu::MultisampleState {
+                    count: SAMPLE_COUNT,
+                    mask: !0,
+                    alpha_to_coverage_enabled: false,
+                },
+                multiview: None,
+                cache: None,
+            })
+        };
+        let mesh_additive_pipeline = {
+            let module = wgsl_mesh::create_shader_module(&device);
+
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some("mesh_additive_pipeline"),
+                layout: Some(&wgsl_mesh::create_pipeline_layout(&device)),
+                vertex: make_vertex_state(
+                    &module,
+                    &wgsl_mesh::entries::vertex_entry_vs_main(
+                        wgpu::VertexStepMode::Vertex,
+                        wgpu::VertexStepMode::Instance,
+                    ),
+                ),
+                fragment: Some(make_fragment_state(
+                    &module,
+                    &wgsl_mesh::entries::fragment_entry_fs_main(&[Some(wgpu::ColorTargetState {
+                        format: surface_config.format,
+                        blend: Some(wgpu::BlendState {
+                            color: wgpu::BlendComponent {
+                                src_factor: wgpu::BlendFactor::SrcAlpha,
+                                dst_factor: wgpu::BlendFactor::One,
+                                operation: wgpu::BlendOperation::Add,
+                            },
+                            alpha: wgpu::BlendComponent::OVER,
+                        }),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })]),
+                )),
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleList,
+                    strip_index_format: None,
+                    front_face: wgpu::FrontFace::Cw,
+                    cull_mode: None,
+                    polygon_mode: wgpu::PolygonMode::Fill,
+                    unclipped_depth: false,
+                    conservative: false,
+                },
                 depth_stencil: draw_depth_stencil,
                 multisample: wgpu::MultisampleState {
                     count: SAMPLE_COUNT,
@@ -341,12 +672,51 @@ impl GPUData {
             ),
         );
 
+        let vertex_buffer = create_geometry_buffer(
+            &device,
+            "vertex_buffer",
+            INITIAL_GEOMETRY_BUFFER_SIZE,
+            wgpu::BufferUsages::VERTEX,
+        );
+        let index_buffer = create_geometry_buffer(
+            &device,
+            "index_buffer",
+            INITIAL_GEOMETRY_BUFFER_SIZE,
+            wgpu::BufferUsages::INDEX,
+        );
+        let instance_buffer = create_geometry_buffer(
+            &device,
+            "instance_buffer",
+            INITIAL_GEOMETRY_BUFFER_SIZE,
+            wgpu::BufferUsages::VERTEX,
+        );
+        let glyph_instance_buffer = create_geometry_buffer(
+            &device,
+            "glyph_instance_buffer",
+            INITIAL_GEOMETRY_BUFFER_SIZE,
+            wgpu::BufferUsages::VERTEX,
+        );
+        let mesh_instance_buffer = create_geometry_buffer(
+            &device,
+            "mesh_instance_buffer",
+            INITIAL_GEOMETRY_BUFFER_SIZE,
+            wgpu::BufferUsages::VERTEX,
+        );
+
+        let timer = GpuTimer::new(&device, &queue);
+
         let mask_atlas = GlyphAtlas::new(&device, ContentType::Mask);
         let color_atlas = GlyphAtlas::new(&device, ContentType::Color);
+        let sdf_atlas = GlyphAtlas::new(&device, ContentType::Sdf);
         let text_atlas_bind_group = create_atlases_bind_group(&device, &mask_atlas, &color_atlas);
+        let glyph_atlas_bind_group =
+            create_glyph_atlases_bind_group(&device, &mask_atlas, &color_atlas, &sdf_atlas);
 
-        Self {
-            surface: Arc::new(surface),
+        Ok(Self {
+            instance,
+            surface,
+            present_modes: surface_caps.present_modes,
+            surface_outdated: false,
             device,
             queue,
             surface_format,
@@ -355,13 +725,86 @@ impl GPUData {
             end_clip_pipeline,
             draw_normal_pipeline,
             draw_additive_pipeline,
+            sprite_normal_pipeline,
+            sprite_additive_pipeline,
+            mesh_normal_pipeline,
+            mesh_additive_pipeline,
+            glyph_normal_pipeline,
+            glyph_additive_pipeline,
             dummy_texture_bind,
+            vertex_buffer,
+            index_buffer,
+            instance_buffer,
+            glyph_instance_buffer,
+            mesh_instance_buffer,
             mask_atlas,
             color_atlas,
+            sdf_atlas,
             text_atlas_bind_group,
+            glyph_atlas_bind_group,
             font_system: cosmic_text::FontSystem::new(),
             swash_cache: cosmic_text::SwashCache::new(),
-        }
+            timer,
+            device_lost,
+        })
+    }
+    /// writes the frame's geometry into the persistent buffers, growing them if they are too small
+    pub fn upload_geometry(
+        &mut self,
+        vertices: &[wgsl_common::structs::VertexInput],
+        indices: &[u32],
+        instances: &[wgsl_sprite::structs::SpriteInstanceInput],
+        glyph_instances: &[wgsl_glyph::structs::GlyphInstanceInput],
+        mesh_instances: &[wgsl_mesh::structs::MeshInstanceInput],
+    ) {
+        write_geometry_buffer(
+            &self.device,
+            &self.queue,
+            &mut self.vertex_buffer,
+            "vertex_buffer",
+            wgpu::BufferUsages::VERTEX,
+            bytemuck::cast_slice(vertices),
+        );
+        write_geometry_buffer(
+            &self.device,
+            &self.queue,
+            &mut self.index_buffer,
+            "index_buffer",
+            wgpu::BufferUsages::INDEX,
+            bytemuck::cast_slice(indices),
+        );
+        write_geometry_buffer(
+            &self.device,
+            &self.queue,
+            &mut self.instance_buffer,
+            "instance_buffer",
+            wgpu::BufferUsages::VERTEX,
+            bytemuck::cast_slice(instances),
+        );
+        write_geometry_buffer(
+            &self.device,
+            &self.queue,
+            &mut self.glyph_instance_buffer,
+            "glyph_instance_buffer",
+            wgpu::BufferUsages::VERTEX,
+            bytemuck::cast_slice(glyph_instances),
+        );
+        write_geometry_buffer(
+            &self.device,
+            &self.queue,
+            &mut self.mesh_instance_buffer,
+            "mesh_instance_buffer",
+            wgpu::BufferUsages::VERTEX,
+            bytemuck::cast_slice(mesh_instances),
+        );
+    }
+    pub fn is_device_lost(&self) -> bool {
+        self.device_lost.load(Ordering::Relaxed)
+    }
+    /// configures the surface again after it was lost or became outdated
+    pub fn reconfigure_surface(&mut self) {
+        self.surface.configure(&self.device, &self.surface_config);
+        self.surface_outdated = false;
     }
     pub fn resize(&mut self, width: u32, height: u32) {
         if width > 0 && height > 0 {
@@ -369,7 +812,7 @@ impl GPUData {
 
             self.surface_config.width = width;
             self.surface_config.height = height;
-            self.surface.configure(&self.device, &self.surface_config);
+            self.reconfigure_surface();
 
             // self.queue.write_buffer(
             //     &self.globals_buffer,
@@ -382,3 +825,39 @@ impl GPUData {
         }
     }
 }
+
+pub fn create_geometry_buffer(
+    device: &wgpu::Device,
+    label: &str,
+    size: u64,
+    usage: wgpu::BufferUsages,
+) -> wgpu::Buffer {
+    device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some(label),
+        size,
+        usage: usage | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    })
+}
+
+/// writes `bytes` to the start of `buffer`, replacing it with a bigger one first if they don't fit
+pub fn write_geometry_buffer(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    buffer: &mut wgpu::Buffer,
+    label: &str,
+    usage: wgpu::BufferUsages,
+    bytes: &[u8],
+) {
+    if buffer.size() < bytes.len() as u64 {
+        *buffer = create_geometry_buffer(
+            device,
+            label,
+            (bytes.len() as u64).next_power_of_two(),
+            usage,
+        );
+    }
+    if !bytes.is_empty() {
+        queue.write_buffer(buffer, 0, bytes);
+    }
+}
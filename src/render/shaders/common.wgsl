@@ -23,6 +23,11 @@ struct VertexOutput {
 };
 
 
+// canvas pixels to clip space
+fn canvas_to_clip(pos: vec2f) -> vec4f {
+    return vec4f(pos / GLOBALS.screen_size * 2.0 * vec2f(1.0, -1.0) + vec2f(-1.0, 1.0), 0.0, 1.0);
+}
+
 fn vs_main_common(
     vertex_idx: u32,
     vertex: VertexInput,
@@ -30,7 +35,7 @@ fn vs_main_common(
 ) -> VertexOutput {
     var out: VertexOutput;
 
-    out.pos = vec4f(vertex.pos / GLOBALS.screen_size * 2.0 * vec2f(1.0, -1.0) + vec2f(-1.0, 1.0), 0.0, 1.0);
+    out.pos = canvas_to_clip(vertex.pos);
     out.color = vertex.color;
     out.uv = uv;
     out.text_uv = vertex.text_uv;
@@ -0,0 +1,117 @@
+#import common::{
+    GLOBALS,
+    canvas_to_clip,
+}
+
+// only textureLoad is used on the mask and color atlases, the sdf atlas is filtered
+@group(1) @binding(0) var TEXT_MASK_T: texture_2d<f32>;
+@group(1) @binding(1) var TEXT_COLOR_T: texture_2d<f32>;
+@group(1) @binding(2) var TEXT_SDF_T: texture_2d<f32>;
+@group(1) @binding(3) var TEXT_SDF_S: sampler;
+
+// glyphs that need more than a plain atlas lookup: outlines, shadows and glows behind text,
+// and glyphs from the sdf atlas. plain glyphs are drawn with the normal vertices in draw.wgsl
+struct GlyphInstanceInput {
+    // columns of the transform mapping the unit quad to canvas space
+    @location(0) transform_x: vec2f,
+    @location(1) transform_y: vec2f,
+    @location(2) translation: vec2f,
+    // atlas coords at the quad's top left and bottom right corners
+    @location(3) uv: vec4f,
+    // the glyph's area in the atlas, nothing outside of it is read
+    @location(4) rect: vec4f,
+    @location(5) color: vec4f,
+    // outline width and blur radius in atlas texels, z is the distance range for sdf glyphs
+    // and zero for glyphs from the mask and color atlases
+    @location(6) effect: vec3f,
+};
+struct GlyphVertexOutput {
+    @builtin(position) pos: vec4f,
+    @location(0) color: vec4f,
+    @location(1) uv: vec2f,
+    @location(2) @interpolate(flat) rect: vec4f,
+    @location(3) @interpolate(flat) effect: vec3f,
+};
+
+@vertex
+fn vs_main(
+    @builtin(vertex_index) vertex_idx: u32,
+    instance: GlyphInstanceInput,
+) -> GlyphVertexOutput {
+    // unit quad corners in triangle strip order
+    let corner = vec2f(f32(vertex_idx & 1u), f32(vertex_idx >> 1u));
+
+    var out: GlyphVertexOutput;
+    out.pos = canvas_to_clip(instance.transform_x * corner.x + instance.transform_y * corner.y + instance.translation);
+    out.color = instance.color;
+    out.uv = mix(instance.uv.xy, instance.uv.zw, corner);
+    out.rect = instance.rect;
+    out.effect = instance.effect;
+    return out;
+}
+
+@fragment
+fn fs_main(in: GlyphVertexOutput) -> @location(0) vec4f {
+    // derivatives are only defined in uniform control flow
+    let texels_per_pixel = length(fwidth(in.uv)) * 0.7071;
+
+    var out = in.color;
+    if in.effect.z > 0.0 {
+        out.a *= sdf_glyph_alpha(in.uv, in.rect, in.effect, texels_per_pixel);
+    } else {
+        out.a *= glyph_effect_alpha(in.uv, in.rect, in.effect);
+    }
+    return out;
+}
+
+// coverage of a single atlas texel of a glyph, zero outside the glyph's rect
+fn glyph_alpha(texel: vec2f, rect: vec4f) -> f32 {
+    if any(texel < rect.xy) || any(texel >= rect.zw) {
+        return 0.0;
+    }
+    if texel.y >= 0.0 {
+        return sqrt(textureLoad(TEXT_MASK_T, vec2i(texel), 0).r);
+    }
+    // color glyphs are stored below the mask atlas, see prepare_glyph
+    let size = vec2f(textureDimensions(TEXT_COLOR_T));
+    return textureLoad(TEXT_COLOR_T, vec2i(texel + vec2f(0.0, 2.0 * size.y)), 0).a;
+}
+
+// same as MAX_EFFECT_RADIUS in render/text/glyph.rs
+const MAX_EFFECT_RADIUS: f32 = 3.0;
+
+// the glyph grown by effect.x texels with its edge softened over effect.y more texels,
+// used for outlines, shadows and glows. only texels within the radius are loaded, at most 37
+fn glyph_effect_alpha(uv: vec2f, rect: vec4f, effect: vec3f) -> f32 {
+    let radius = min(effect.x + effect.y, MAX_EFFECT_RADIUS);
+    let steps = i32(ceil(radius));
+
+    var alpha = 0.0;
+    for (var y = -steps; y <= steps; y++) {
+        for (var x = -steps; x <= steps; x++) {
+            let offset = vec2f(f32(x), f32(y));
+            let distance = length(offset);
+            if distance > radius + 0.5 {
+                continue;
+            }
+            let falloff = 1.0 - smoothstep(effect.x, radius + 0.5, distance);
+            alpha = max(alpha, glyph_alpha(floor(uv + offset), rect) * falloff);
+        }
+    }
+    return alpha;
+}
+
+// glyphs from the sdf atlas store the distance to their edge, so they stay sharp at any scale.
+// outlines and soft edges just move and widen the threshold, up to the stored distance range
+fn sdf_glyph_alpha(uv: vec2f, rect: vec4f, effect: vec3f, texels_per_pixel: f32) -> f32 {
+    let clamped = clamp(uv, rect.xy + 0.5, rect.zw - 0.5);
+    let value = textureSampleLevel(
+        TEXT_SDF_T,
+        TEXT_SDF_S,
+        clamped / vec2f(textureDimensions(TEXT_SDF_T)),
+        0.0,
+    ).r;
+    let distance = (value - 0.5) * 2.0 * effect.z + effect.x;
+    let aa = max(texels_per_pixel * 0.5, 0.001);
+    return smoothstep(-aa - effect.y, aa, distance);
+}
@@ -0,0 +1,38 @@
+#import common::{
+    VertexInput,
+    VertexOutput,
+    vs_main_common,
+}
+
+@group(1) @binding(0) var TEXTURE_T: texture_2d<f32>;
+@group(1) @binding(1) var TEXTURE_S: sampler;
+
+struct MeshInstanceInput {
+    // columns of the canvas transform the mesh is drawn with
+    @location(4) transform_x: vec2f,
+    @location(5) transform_y: vec2f,
+    @location(6) translation: vec2f,
+    @location(7) tint: vec4f,
+};
+
+@vertex
+fn vs_main(
+    @builtin(vertex_index) vertex_idx: u32,
+    vertex: VertexInput,
+    instance: MeshInstanceInput,
+) -> VertexOutput {
+    var transformed = vertex;
+    transformed.pos = instance.transform_x * vertex.pos.x + instance.transform_y * vertex.pos.y + instance.translation;
+    transformed.color = vertex.color * instance.tint;
+
+    return vs_main_common(vertex_idx, transformed, vertex.uv / vec2f(textureDimensions(TEXTURE_T)));
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4f {
+    var out = in.color;
+    if in.uv.x >= 0.0 {
+        out *= textureSample(TEXTURE_T, TEXTURE_S, in.uv);
+    }
+    return out;
+}
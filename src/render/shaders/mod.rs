@@ -4,5 +4,8 @@ mod out;
 
 pub use out::common as wgsl_common;
 pub use out::draw as wgsl_draw;
+pub use out::glyph as wgsl_glyph;
+pub use out::mesh as wgsl_mesh;
+pub use out::sprite as wgsl_sprite;
 pub use out::stencil as wgsl_stencil;
 pub use out::{make_fragment_state, make_vertex_state};
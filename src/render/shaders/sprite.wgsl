@@ -0,0 +1,40 @@
+#import common::{
+    VertexInput,
+    VertexOutput,
+    vs_main_common,
+}
+
+@group(1) @binding(0) var TEXTURE_T: texture_2d<f32>;
+@group(1) @binding(1) var TEXTURE_S: sampler;
+
+struct SpriteInstanceInput {
+    // columns of the transform mapping the unit quad to canvas space
+    @location(0) transform_x: vec2f,
+    @location(1) transform_y: vec2f,
+    @location(2) translation: vec2f,
+    // position and size of the drawn region in texture pixels
+    @location(3) region: vec4f,
+    @location(4) color: vec4f,
+};
+
+@vertex
+fn vs_main(
+    @builtin(vertex_index) vertex_idx: u32,
+    instance: SpriteInstanceInput,
+) -> VertexOutput {
+    // unit quad corners in triangle strip order
+    let corner = vec2f(f32(vertex_idx & 1u), f32(vertex_idx >> 1u));
+
+    var vertex: VertexInput;
+    vertex.pos = instance.transform_x * corner.x + instance.transform_y * corner.y + instance.translation;
+    vertex.color = instance.color;
+    vertex.uv = instance.region.xy + instance.region.zw * corner;
+    vertex.text_uv = vec2f(-1.0, 0.0);
+
+    return vs_main_common(vertex_idx, vertex, vertex.uv / vec2f(textureDimensions(TEXTURE_T)));
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4f {
+    return in.color * textureSample(TEXTURE_T, TEXTURE_S, in.uv);
+}
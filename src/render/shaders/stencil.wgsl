@@ -1,4 +1,3 @@
-
 #import common::{
     VertexInput,
     VertexOutput,
@@ -16,4 +15,18 @@ fn vs_main(
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4f {
     return vec4(1.0);
-}
\ No newline at end of file
+}
+
+// oversized triangle covering the whole target, independent of the canvas size
+@vertex
+fn vs_fullscreen(
+    @builtin(vertex_index) vertex_idx: u32,
+) -> @builtin(position) vec4f {
+    let pos = vec2f(f32((vertex_idx << 1u) & 2u), f32(vertex_idx & 2u));
+    return vec4f(pos * 2.0 - 1.0, 0.0, 1.0);
+}
+
+@fragment
+fn fs_fullscreen() -> @location(0) vec4f {
+    return vec4(1.0);
+}
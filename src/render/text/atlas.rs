@@ -3,9 +3,15 @@ use std::collections::HashSet;
 use etagere::{Allocation, BucketedAtlasAllocator, size2};
 use lru::LruCache;
 
-use crate::render::{shaders::wgsl_draw, texture::TextureBundle};
+use crate::render::{
+    shaders::{wgsl_draw, wgsl_glyph},
+    texture::TextureBundle,
+};
 
-use super::glyph::{ContentType, GlyphCacheStatus, GlyphData};
+use super::{
+    glyph::{ContentType, GlyphCacheStatus, GlyphData},
+    sdf,
+};
 
 #[allow(dead_code)]
 pub struct GlyphAtlas {
@@ -35,7 +41,7 @@ impl GlyphAtlas {
             texture_size,
             texture_size,
             typ.texture_format(),
-            wgpu::FilterMode::Nearest,
+            typ.filter_mode(),
             wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
             1,
             1,
@@ -113,7 +119,7 @@ impl GlyphAtlas {
             new_size,
             new_size,
             self.typ.texture_format(),
-            wgpu::FilterMode::Nearest,
+            self.typ.filter_mode(),
             wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
             1,
             1,
@@ -126,7 +132,13 @@ impl GlyphAtlas {
             };
 
             let (image_data, width, height) = {
-                let image = cache.get_image_uncached(font_system, cache_key).unwrap();
+                let image = match self.typ {
+                    ContentType::Sdf => sdf::rasterize_sdf(font_system, cache, cache_key),
+                    ContentType::Mask | ContentType::Color => {
+                        cache.get_image_uncached(font_system, cache_key)
+                    }
+                }
+                .unwrap();
                 let width = image.placement.width as usize;
                 let height = image.placement.height as usize;
 
@@ -185,3 +197,23 @@ pub fn create_atlases_bind_group(
         wgsl_draw::globals::BindGroup2Entries::new(params),
     )
 }
+
+/// the atlases for the glyph pipeline, which also reads the sdf atlas
+pub fn create_glyph_atlases_bind_group(
+    device: &wgpu::Device,
+    mask: &GlyphAtlas,
+    color: &GlyphAtlas,
+    sdf: &GlyphAtlas,
+) -> wgsl_glyph::globals::BindGroup1 {
+    let params = wgsl_glyph::globals::BindGroup1EntriesEntriesParams {
+        TEXT_MASK_T: &mask.texture.view,
+        TEXT_COLOR_T: &color.texture.view,
+        TEXT_SDF_T: &sdf.texture.view,
+        TEXT_SDF_S: &sdf.texture.sampler,
+    };
+
+    wgsl_glyph::globals::BindGroup1::from_bindings(
+        device,
+        wgsl_glyph::globals::BindGroup1Entries::new(params),
+    )
+}
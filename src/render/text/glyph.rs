@@ -1,6 +1,11 @@
 use etagere::AllocId;
+use glam::{Affine2, Vec2, vec2};
 
-use crate::render::{GPUData, shaders::wgsl_common, text::atlas::create_atlases_bind_group};
+use crate::render::{
+    GPUData,
+    shaders::{wgsl_common, wgsl_glyph},
+    text::atlas::{create_atlases_bind_group, create_glyph_atlases_bind_group},
+};
 
 /// some glyphs are just a mask where each pixel is a 0-255 value, like most text
 /// other glyphs are full color, like emojis
@@ -8,12 +13,14 @@ use crate::render::{GPUData, shaders::wgsl_common, text::atlas::create_atlases_b
 pub enum ContentType {
     Mask,
     Color,
+    /// the distance to the glyph's edge, see `sdf.rs`
+    Sdf,
 }
 impl ContentType {
     /// how many values are used per pixel
     pub fn channel_count(self) -> usize {
         match self {
-            Self::Mask => 1,
+            Self::Mask | Self::Sdf => 1,
             Self::Color => 4,
         }
     }
@@ -21,10 +28,18 @@ impl ContentType {
     /// you get it
     pub fn texture_format(self) -> wgpu::TextureFormat {
         match self {
-            Self::Mask => wgpu::TextureFormat::R8Unorm,
+            Self::Mask | Self::Sdf => wgpu::TextureFormat::R8Unorm,
             Self::Color => wgpu::TextureFormat::Rgba8Unorm,
         }
     }
+
+    /// distance fields are sampled between texels, everything else is drawn texel for texel
+    pub fn filter_mode(self) -> wgpu::FilterMode {
+        match self {
+            Self::Mask | Self::Color => wgpu::FilterMode::Nearest,
+            Self::Sdf => wgpu::FilterMode::Linear,
+        }
+    }
 }
 
 /// data about a glyph in the atlases
@@ -59,13 +74,13 @@ pub fn prepare_glyph(
     let (data, atlas_size) =
         if let Some(d) = gpu_data.mask_atlas.glyph_cache.get(&physical.cache_key) {
             gpu_data.mask_atlas.glyphs_in_use.insert(physical.cache_key);
-            (d, gpu_data.mask_atlas.texture_size as f32)
+            (*d, gpu_data.mask_atlas.texture_size as f32)
         } else if let Some(d) = gpu_data.color_atlas.glyph_cache.get(&physical.cache_key) {
             gpu_data
                 .color_atlas
                 .glyphs_in_use
                 .insert(physical.cache_key);
-            (d, gpu_data.color_atlas.texture_size as f32)
+            (*d, gpu_data.color_atlas.texture_size as f32)
         } else {
             let image = gpu_data
                 .swash_cache
@@ -76,94 +91,13 @@ pub fn prepare_glyph(
                 cosmic_text::SwashContent::Mask => ContentType::Mask,
                 cosmic_text::SwashContent::SubpixelMask => ContentType::Mask,
             };
-
-            let nonzero = image.placement.width > 0 && image.placement.height > 0;
-
-            let mut atlas = match content_type {
-                ContentType::Mask => &mut gpu_data.mask_atlas,
-                ContentType::Color => &mut gpu_data.color_atlas,
+            let data = upload_glyph(gpu_data, content_type, physical.cache_key, &image)?;
+            let atlas_size = match content_type {
+                ContentType::Mask => gpu_data.mask_atlas.texture_size,
+                ContentType::Color => gpu_data.color_atlas.texture_size,
+                ContentType::Sdf => gpu_data.sdf_atlas.texture_size,
             };
-
-            let cache_status = if nonzero {
-                let alloc = loop {
-                    match atlas.try_alloc(
-                        image.placement.width as usize,
-                        image.placement.height as usize,
-                    ) {
-                        Some(a) => break a,
-                        None => {
-                            if !atlas.grow(
-                                &gpu_data.device,
-                                &gpu_data.queue,
-                                &mut gpu_data.font_system,
-                                &mut gpu_data.swash_cache,
-                            ) {
-                                // full atlas
-                                return None;
-                            }
-
-                            gpu_data.text_atlas_bind_group = create_atlases_bind_group(
-                                &gpu_data.device,
-                                &gpu_data.mask_atlas,
-                                &gpu_data.color_atlas,
-                            );
-
-                            atlas = match content_type {
-                                ContentType::Mask => &mut gpu_data.mask_atlas,
-                                ContentType::Color => &mut gpu_data.color_atlas,
-                            };
-                        }
-                    }
-                };
-                let atlas_min = alloc.rectangle.min;
-
-                gpu_data.queue.write_texture(
-                    wgpu::TexelCopyTextureInfo {
-                        texture: &atlas.texture.texture,
-                        mip_level: 0,
-                        origin: wgpu::Origin3d {
-                            x: atlas_min.x as u32,
-                            y: atlas_min.y as u32,
-                            z: 0,
-                        },
-                        aspect: wgpu::TextureAspect::All,
-                    },
-                    &image.data,
-                    wgpu::TexelCopyBufferLayout {
-                        offset: 0,
-                        bytes_per_row: Some(image.placement.width * atlas.channel_count() as u32),
-                        rows_per_image: None,
-                    },
-                    wgpu::Extent3d {
-                        width: image.placement.width,
-                        height: image.placement.height,
-                        depth_or_array_layers: 1,
-                    },
-                );
-
-                GlyphCacheStatus::InAtlas {
-                    x: atlas_min.x as u16,
-                    y: atlas_min.y as u16,
-                    content_type,
-                    alloc_id: alloc.id,
-                }
-            } else {
-                GlyphCacheStatus::ZeroSized
-            };
-
-            atlas.glyphs_in_use.insert(physical.cache_key);
-            (
-                atlas
-                    .glyph_cache
-                    .get_or_insert(physical.cache_key, || GlyphData {
-                        width: image.placement.width as u16,
-                        height: image.placement.height as u16,
-                        top: image.placement.top as i16,
-                        left: image.placement.left as i16,
-                        cache_status,
-                    }),
-                atlas.texture_size as f32,
-            )
+            (data, atlas_size as f32)
         };
 
     let x = physical.x as f32 + data.left as f32 + offset_x;
@@ -173,7 +107,7 @@ pub fn prepare_glyph(
         GlyphCacheStatus::InAtlas {
             x, y, content_type, ..
         } => match content_type {
-            ContentType::Mask => (x as f32, y as f32),
+            ContentType::Mask | ContentType::Sdf => (x as f32, y as f32),
             ContentType::Color => (x as f32, y as f32 - 2.0 * atlas_size),
         },
         GlyphCacheStatus::ZeroSized => return None,
@@ -184,26 +118,246 @@ pub fn prepare_glyph(
 
     let points =
         [[0.0, 0.0], [width, 0.0], [width, height], [0.0, height]].map(|[p0, p1]| [p0 + x, p1 + y]);
+    let text_uvs = [
+        [atlas_x, atlas_y],
+        [atlas_x + width, atlas_y],
+        [atlas_x + width, atlas_y + height],
+        [atlas_x, atlas_y + height],
+    ];
+
+    Some(std::array::from_fn(|i| {
+        wgsl_common::structs::VertexInput::new(points[i], color, [-1.0, 0.0], text_uvs[i])
+    }))
+}
+
+/// puts a rasterized glyph into the atlas for its content type, growing the atlas when it's full.
+/// returns none if the atlas can't grow any further
+pub fn upload_glyph(
+    gpu_data: &mut GPUData,
+    content_type: ContentType,
+    cache_key: cosmic_text::CacheKey,
+    image: &cosmic_text::SwashImage,
+) -> Option<GlyphData> {
+    let nonzero = image.placement.width > 0 && image.placement.height > 0;
+
+    let mut atlas = match content_type {
+        ContentType::Mask => &mut gpu_data.mask_atlas,
+        ContentType::Color => &mut gpu_data.color_atlas,
+        ContentType::Sdf => &mut gpu_data.sdf_atlas,
+    };
+
+    let cache_status = if nonzero {
+        let alloc = loop {
+            match atlas.try_alloc(
+                image.placement.width as usize,
+                image.placement.height as usize,
+            ) {
+                Some(a) => break a,
+                None => {
+                    if !atlas.grow(
+                        &gpu_data.device,
+                        &gpu_data.queue,
+                        &mut gpu_data.font_system,
+                        &mut gpu_data.swash_cache,
+                    ) {
+                        // full atlas
+                        return None;
+                    }
+
+                    gpu_data.text_atlas_bind_group = create_atlases_bind_group(
+                        &gpu_data.device,
+                        &gpu_data.mask_atlas,
+                        &gpu_data.color_atlas,
+                    );
+                    gpu_data.glyph_atlas_bind_group = create_glyph_atlases_bind_group(
+                        &gpu_data.device,
+                        &gpu_data.mask_atlas,
+                        &gpu_data.color_atlas,
+                        &gpu_data.sdf_atlas,
+                    );
+
+                    atlas = match content_type {
+                        ContentType::Mask => &mut gpu_data.mask_atlas,
+                        ContentType::Color => &mut gpu_data.color_atlas,
+                        ContentType::Sdf => &mut gpu_data.sdf_atlas,
+                    };
+                }
+            }
+        };
+        let atlas_min = alloc.rectangle.min;
+
+        gpu_data.queue.write_texture(
+            wgpu::TexelCopyTextureInfo {
+                texture: &atlas.texture.texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d {
+                    x: atlas_min.x as u32,
+                    y: atlas_min.y as u32,
+                    z: 0,
+                },
+                aspect: wgpu::TextureAspect::All,
+            },
+            &image.data,
+            wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(image.placement.width * atlas.channel_count() as u32),
+                rows_per_image: None,
+            },
+            wgpu::Extent3d {
+                width: image.placement.width,
+                height: image.placement.height,
+                depth_or_array_layers: 1,
+            },
+        );
+
+        GlyphCacheStatus::InAtlas {
+            x: atlas_min.x as u16,
+            y: atlas_min.y as u16,
+            content_type,
+            alloc_id: alloc.id,
+        }
+    } else {
+        GlyphCacheStatus::ZeroSized
+    };
+
+    atlas.glyphs_in_use.insert(cache_key);
+    Some(*atlas.glyph_cache.get_or_insert(cache_key, || GlyphData {
+        width: image.placement.width as u16,
+        height: image.placement.height as u16,
+        top: image.placement.top as i16,
+        left: image.placement.left as i16,
+        cache_status,
+    }))
+}
+
+/// a glyph quad before the canvas transform. plain glyphs are drawn as normal vertices, glyphs
+/// with an effect or from the sdf atlas need the glyph pipeline and are drawn as instances
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GlyphQuad {
+    /// top left and bottom right corners
+    pub min: Vec2,
+    pub max: Vec2,
+    /// atlas coords at `min` and `max`
+    pub uv_min: Vec2,
+    pub uv_max: Vec2,
+    /// the glyph's area in the atlas, nothing outside of it is drawn
+    pub rect: [f32; 4],
+    pub color: [f32; 4],
+    /// outline width and blur radius in atlas texels, then the sdf distance range or zero
+    pub effect: [f32; 3],
+}
+
+impl GlyphQuad {
+    /// the quad of the vertices from `prepare_glyph`
+    pub fn from_vertices(quad: [wgsl_common::structs::VertexInput; 4]) -> Self {
+        let (uv_min, uv_max) = (quad[0].text_uv, quad[2].text_uv);
+        Self {
+            min: Vec2::from_array(quad[0].pos),
+            max: Vec2::from_array(quad[2].pos),
+            uv_min: Vec2::from_array(uv_min),
+            uv_max: Vec2::from_array(uv_max),
+            rect: [uv_min[0], uv_min[1], uv_max[0], uv_max[1]],
+            color: quad[0].color,
+            effect: [0.0; 3],
+        }
+    }
+    /// whether the quad is a plain mask or color glyph that the draw pipeline can draw
+    pub fn is_plain(&self) -> bool {
+        self.effect == [0.0; 3]
+    }
+    /// normal vertices for a plain quad
+    pub fn vertices(&self, transform: Affine2) -> [wgsl_common::structs::VertexInput; 4] {
+        [
+            vec2(0.0, 0.0),
+            vec2(1.0, 0.0),
+            vec2(1.0, 1.0),
+            vec2(0.0, 1.0),
+        ]
+        .map(|corner| {
+            wgsl_common::structs::VertexInput::new(
+                transform
+                    .transform_point2(self.min + (self.max - self.min) * corner)
+                    .to_array(),
+                self.color,
+                [-1.0, 0.0],
+                (self.uv_min + (self.uv_max - self.uv_min) * corner).to_array(),
+            )
+        })
+    }
+    /// an instance for the glyph pipeline, for quads that aren't plain
+    pub fn instance(&self, transform: Affine2) -> wgsl_glyph::structs::GlyphInstanceInput {
+        let t =
+            transform * Affine2::from_scale_angle_translation(self.max - self.min, 0.0, self.min);
+        wgsl_glyph::structs::GlyphInstanceInput::new(
+            t.matrix2.x_axis.to_array(),
+            t.matrix2.y_axis.to_array(),
+            t.translation.to_array(),
+            [self.uv_min.x, self.uv_min.y, self.uv_max.x, self.uv_max.y],
+            self.rect,
+            self.color,
+            self.effect,
+        )
+    }
+}
+
+/// cuts a glyph quad down to the part inside the `min`..`max` box, moving the atlas coords along
+/// with the corners
+pub fn clip_glyph(mut quad: GlyphQuad, min: Vec2, max: Vec2) -> Option<GlyphQuad> {
+    let lo = quad.min.max(min);
+    let hi = quad.max.min(max);
+    if lo.x >= hi.x || lo.y >= hi.y {
+        return None;
+    }
+
+    // atlas texels per pixel, sdf glyphs are scaled to the text size
+    let scale = (quad.uv_max - quad.uv_min) / (quad.max - quad.min);
+    quad.uv_min += (lo - quad.min) * scale;
+    quad.uv_max += (hi - quad.max) * scale;
+    quad.min = lo;
+    quad.max = hi;
+    Some(quad)
+}
+
+/// the most pixels an effect on a normal glyph reaches, outline and blur together. the shader
+/// loads every atlas texel within this radius for each pixel, so it's kept small. sdf glyphs
+/// aren't affected, their effects are limited by `SDF_RANGE` instead
+pub const MAX_EFFECT_RADIUS: f32 = 3.0;
+
+/// a quad for an outline, shadow or glow behind a glyph quad from `prepare_glyph` or
+/// `prepare_sdf_glyph`. it covers the glyph expanded by `outline` pixels plus a soft edge of
+/// `blur` pixels, moved by `offset`. for normal glyphs both together are capped at
+/// `MAX_EFFECT_RADIUS`, the outline first
+pub fn glyph_effect(
+    mut quad: GlyphQuad,
+    outline: f32,
+    blur: f32,
+    offset: Vec2,
+    color: [f32; 4],
+) -> GlyphQuad {
+    let sdf_range = quad.effect[2];
+    let (grow, effect) = if sdf_range > 0.0 {
+        // sdf quads already include the distance range around the glyph, so the effect just has
+        // to be converted to atlas texels
+        let scale = (quad.uv_max.x - quad.uv_min.x) / (quad.max.x - quad.min.x);
+        (
+            0.0,
+            [outline.max(0.0) * scale, blur.max(0.0) * scale, sdf_range],
+        )
+    } else {
+        let outline = outline.clamp(0.0, MAX_EFFECT_RADIUS);
+        let blur = blur.clamp(0.0, MAX_EFFECT_RADIUS - outline);
+        // never all zero so the quad isn't drawn as a plain glyph, e.g. for hard shadows
+        (
+            (outline + blur).ceil() + 1.0,
+            [outline.max(1e-3), blur, 0.0],
+        )
+    };
 
-    Some([
-        wgsl_common::structs::VertexInput::new(points[0], color, [-1.0, 0.0], [atlas_x, atlas_y]),
-        wgsl_common::structs::VertexInput::new(
-            points[1],
-            color,
-            [-1.0, 0.0],
-            [atlas_x + width, atlas_y],
-        ),
-        wgsl_common::structs::VertexInput::new(
-            points[2],
-            color,
-            [-1.0, 0.0],
-            [atlas_x + width, atlas_y + height],
-        ),
-        wgsl_common::structs::VertexInput::new(
-            points[3],
-            color,
-            [-1.0, 0.0],
-            [atlas_x, atlas_y + height],
-        ),
-    ])
+    quad.min += offset - grow;
+    quad.max += offset + grow;
+    quad.uv_min -= grow;
+    quad.uv_max += grow;
+    quad.color = color;
+    quad.effect = effect;
+    quad
 }
@@ -6,6 +6,7 @@ use std::hash::Hash;
 
 pub mod atlas;
 pub mod glyph;
+pub mod sdf;
 
 /// finds the closest matched font attributes for the input
 pub fn find_closest_attrs<'a>(
@@ -0,0 +1,152 @@
+//! glyphs stored as signed distance fields: every texel holds how far it is from the glyph's edge
+//! instead of how much of it is covered. they're rendered once at `SDF_SIZE` and scaled to any
+//! text size or transform in the shader while staying sharp
+
+use glam::vec2;
+
+use crate::render::{
+    GPUData,
+    text::glyph::{ContentType, GlyphCacheStatus, GlyphQuad, upload_glyph},
+};
+
+/// font size the distance fields are generated at
+pub const SDF_SIZE: f32 = 48.0;
+/// how many texels the field reaches out from the glyph's edge on either side.
+/// outlines and soft edges can't extend past it
+pub const SDF_RANGE: u32 = 6;
+
+/// renders a glyph and turns its coverage into a distance field padded by `SDF_RANGE` on each side.
+/// color glyphs have no edge to measure and come back as an empty image
+pub fn rasterize_sdf(
+    font_system: &mut cosmic_text::FontSystem,
+    cache: &mut cosmic_text::SwashCache,
+    cache_key: cosmic_text::CacheKey,
+) -> Option<cosmic_text::SwashImage> {
+    let mut image = cache.get_image_uncached(font_system, cache_key)?;
+    let placement = &mut image.placement;
+
+    if image.content == cosmic_text::SwashContent::Color
+        || placement.width == 0
+        || placement.height == 0
+    {
+        placement.width = 0;
+        placement.height = 0;
+        image.data.clear();
+        return Some(image);
+    }
+    if image.content == cosmic_text::SwashContent::SubpixelMask {
+        // keeps one of the three subpixel channels
+        image.data = image.data.chunks_exact(4).map(|p| p[1]).collect();
+    }
+
+    image.data = distance_field(
+        &image.data,
+        placement.width as usize,
+        placement.height as usize,
+    );
+    image.content = cosmic_text::SwashContent::Mask;
+    placement.width += 2 * SDF_RANGE;
+    placement.height += 2 * SDF_RANGE;
+    placement.left -= SDF_RANGE as i32;
+    placement.top += SDF_RANGE as i32;
+    Some(image)
+}
+
+/// brute force search for the closest texel on the other side of the edge, within `SDF_RANGE`.
+/// 0.5 is on the edge, higher values are inside
+fn distance_field(coverage: &[u8], width: usize, height: usize) -> Vec<u8> {
+    let range = SDF_RANGE as isize;
+    let (out_width, out_height) = (width as isize + 2 * range, height as isize + 2 * range);
+    let coverage_at = |x: isize, y: isize| {
+        let (x, y) = (x - range, y - range);
+        if x < 0 || y < 0 || x >= width as isize || y >= height as isize {
+            0
+        } else {
+            coverage[y as usize * width + x as usize]
+        }
+    };
+
+    let mut field = Vec::with_capacity((out_width * out_height) as usize);
+    for y in 0..out_height {
+        for x in 0..out_width {
+            let value = coverage_at(x, y);
+            let inside = value >= 128;
+
+            let distance = if value > 0 && value < 255 {
+                // partly covered texels sit on the edge
+                value as f32 / 255.0 - 0.5
+            } else {
+                let mut closest = (range * range + 1) as f32;
+                for dy in -range..=range {
+                    for dx in -range..=range {
+                        if (coverage_at(x + dx, y + dy) >= 128) != inside {
+                            closest = closest.min((dx * dx + dy * dy) as f32);
+                        }
+                    }
+                }
+                let distance = (closest.sqrt() - 0.5).min(range as f32);
+                if inside { distance } else { -distance }
+            };
+
+            let normalized = 0.5 + distance / (2.0 * range as f32);
+            field.push((normalized * 255.0).round().clamp(0.0, 255.0) as u8);
+        }
+    }
+    field
+}
+
+/// like `prepare_glyph` but from the sdf atlas, placed at the glyph's unrounded position and scaled
+/// to its font size. color glyphs like emojis aren't in the sdf atlas and return none
+pub fn prepare_sdf_glyph(
+    glyph: &cosmic_text::LayoutGlyph,
+    line_y: f32,
+    gpu_data: &mut GPUData,
+    color: [f32; 4],
+    offset_x: f32,
+    offset_y: f32,
+) -> Option<GlyphQuad> {
+    let (cache_key, _, _) = cosmic_text::CacheKey::new(
+        glyph.font_id,
+        glyph.glyph_id,
+        SDF_SIZE,
+        (0.0, 0.0),
+        glyph.cache_key_flags,
+    );
+
+    let data = if let Some(d) = gpu_data.sdf_atlas.glyph_cache.get(&cache_key) {
+        gpu_data.sdf_atlas.glyphs_in_use.insert(cache_key);
+        *d
+    } else {
+        let image = rasterize_sdf(
+            &mut gpu_data.font_system,
+            &mut gpu_data.swash_cache,
+            cache_key,
+        )?;
+        upload_glyph(gpu_data, ContentType::Sdf, cache_key, &image)?
+    };
+    let GlyphCacheStatus::InAtlas { x, y, .. } = data.cache_status else {
+        return None;
+    };
+
+    let scale = glyph.font_size / SDF_SIZE;
+    let left = offset_x + glyph.x + glyph.x_offset * glyph.font_size + data.left as f32 * scale;
+    let top =
+        offset_y + line_y + glyph.y - glyph.y_offset * glyph.font_size - data.top as f32 * scale;
+    let size = vec2(data.width as f32, data.height as f32);
+    let atlas_pos = vec2(x as f32, y as f32);
+
+    Some(GlyphQuad {
+        min: vec2(left, top),
+        max: vec2(left, top) + size * scale,
+        uv_min: atlas_pos,
+        uv_max: atlas_pos + size,
+        rect: [
+            atlas_pos.x,
+            atlas_pos.y,
+            atlas_pos.x + size.x,
+            atlas_pos.y + size.y,
+        ],
+        color,
+        effect: [0.0, 0.0, SDF_RANGE as f32],
+    })
+}
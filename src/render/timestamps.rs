@@ -0,0 +1,127 @@
+use std::{sync::Arc, time::Duration};
+
+use parking_lot::Mutex;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum ReadbackState {
+    Idle,
+    Copied,
+    Mapping,
+    Mapped,
+}
+
+/// measures how long the gpu spends on a frame, from the start of its first render pass to the end
+/// of its last one. results are read back asynchronously so they arrive a frame or two late
+pub struct GpuTimer {
+    query_set: wgpu::QuerySet,
+    resolve_buffer: wgpu::Buffer,
+    readback_buffer: wgpu::Buffer,
+    period: f32,
+    state: Arc<Mutex<ReadbackState>>,
+}
+
+impl GpuTimer {
+    const SIZE: u64 = 2 * wgpu::QUERY_SIZE as u64;
+
+    /// `None` if the device wasn't created with `Features::TIMESTAMP_QUERY`
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue) -> Option<Self> {
+        if !device.features().contains(wgpu::Features::TIMESTAMP_QUERY) {
+            return None;
+        }
+
+        let query_set = device.create_query_set(&wgpu::QuerySetDescriptor {
+            label: Some("frame_timestamps"),
+            ty: wgpu::QueryType::Timestamp,
+            count: 2,
+        });
+        let resolve_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("timestamp_resolve_buffer"),
+            size: Self::SIZE,
+            usage: wgpu::BufferUsages::QUERY_RESOLVE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let readback_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("timestamp_readback_buffer"),
+            size: Self::SIZE,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        Some(Self {
+            query_set,
+            resolve_buffer,
+            readback_buffer,
+            period: queue.get_timestamp_period(),
+            state: Arc::new(Mutex::new(ReadbackState::Idle)),
+        })
+    }
+    pub fn timestamp_writes(
+        &self,
+        first_pass: bool,
+        last_pass: bool,
+    ) -> Option<wgpu::RenderPassTimestampWrites<'_>> {
+        (first_pass || last_pass).then_some(wgpu::RenderPassTimestampWrites {
+            query_set: &self.query_set,
+            beginning_of_pass_write_index: first_pass.then_some(0),
+            end_of_pass_write_index: last_pass.then_some(1),
+        })
+    }
+    /// copies this frame's timestamps for reading, skipped while the previous ones are still in flight
+    pub fn resolve(&self, encoder: &mut wgpu::CommandEncoder) {
+        let mut state = self.state.lock();
+        if *state != ReadbackState::Idle {
+            return;
+        }
+        encoder.resolve_query_set(&self.query_set, 0..2, &self.resolve_buffer, 0);
+        encoder.copy_buffer_to_buffer(
+            &self.resolve_buffer,
+            0,
+            &self.readback_buffer,
+            0,
+            Self::SIZE,
+        );
+        *state = ReadbackState::Copied;
+    }
+    /// starts reading back the timestamps resolved this frame, call after submitting
+    pub fn map(&self) {
+        {
+            let mut state = self.state.lock();
+            if *state != ReadbackState::Copied {
+                return;
+            }
+            *state = ReadbackState::Mapping;
+        }
+
+        let state = self.state.clone();
+        self.readback_buffer
+            .slice(..)
+            .map_async(wgpu::MapMode::Read, move |result| {
+                *state.lock() = match result {
+                    Ok(()) => ReadbackState::Mapped,
+                    Err(_) => ReadbackState::Idle,
+                };
+            });
+    }
+    /// the gpu time of the latest frame whose timestamps finished reading back since the last call
+    pub fn poll(&self, device: &wgpu::Device) -> Option<Duration> {
+        _ = device.poll(wgpu::PollType::Poll);
+
+        let mut state = self.state.lock();
+        if *state != ReadbackState::Mapped {
+            return None;
+        }
+
+        let [start, end] = {
+            let view = self.readback_buffer.slice(..).get_mapped_range();
+            let timestamps: &[u64] = bytemuck::cast_slice(&view);
+            [timestamps[0], timestamps[1]]
+        };
+        self.readback_buffer.unmap();
+        *state = ReadbackState::Idle;
+
+        let ticks = end.saturating_sub(start);
+        Some(Duration::from_nanos(
+            (ticks as f64 * self.period as f64) as u64,
+        ))
+    }
+}
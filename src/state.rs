@@ -1,11 +1,46 @@
-use crate::{canvas::Canvas, context::Context};
+use crate::{canvas::Canvas, context::Context, error::MapleError};
 
 pub trait AppState {
     fn setup(ctx: &mut Context) -> Self;
+    /// picks the backends to retry with when creating the window's gpu device fails, `tried` holds
+    /// every backend attempted so far. returning `None` makes `run_app` return the error
+    fn fallback_backends(error: &MapleError, tried: wgpu::Backends) -> Option<wgpu::Backends>
+    where
+        Self: Sized,
+    {
+        None
+    }
     fn fixed_update(&mut self, ctx: &mut Context);
+    /// called once per render frame before `draw`, `dt` is the same as `Context::delta_time`
+    fn update(&mut self, dt: f32, ctx: &mut Context) {}
     fn draw(&mut self, canvas: &mut Canvas);
+    /// called after the gpu device was lost and recreated. textures and meshes are restored,
+    /// canvas contents are not and have to be drawn again
+    fn device_restored(&mut self, ctx: &mut Context) {}
 
     fn key_event(&mut self, event: winit::event::KeyEvent, ctx: &mut Context) {}
     fn mouse_input(&mut self, button: winit::event::MouseButton, pressed: bool, ctx: &mut Context) {
     }
+    /// text typed on the keyboard or committed by the ime, without control characters
+    fn text_input(&mut self, text: &str, ctx: &mut Context) {}
+    /// the ime's text that is still being composed, replacing the previous preedit. `cursor` is the
+    /// byte range of the selection inside it. an empty `text` means composing ended or was cancelled
+    fn ime_preedit(&mut self, text: &str, cursor: Option<(usize, usize)>, ctx: &mut Context) {}
+
+    /// the window's drawable area changed, the main canvas is resized to match before the next frame
+    fn resized(&mut self, width: u32, height: u32, ctx: &mut Context) {}
+    fn focus_changed(&mut self, focused: bool, ctx: &mut Context) {}
+    fn cursor_entered(&mut self, ctx: &mut Context) {}
+    fn cursor_left(&mut self, ctx: &mut Context) {}
+    /// the window became fully hidden or visible again, not reported on every platform
+    fn occluded(&mut self, occluded: bool, ctx: &mut Context) {}
+    /// the window moved to a monitor with a different dpi scale or the scale setting changed
+    fn scale_factor_changed(&mut self, scale_factor: f64, ctx: &mut Context) {}
+    /// called once per file dropped onto the window
+    fn file_dropped(&mut self, path: std::path::PathBuf, ctx: &mut Context) {}
+    /// the user tried to close the window. returning false keeps it open,
+    /// `Context::exit` can close it later, e.g. after asking to save
+    fn close_requested(&mut self, ctx: &mut Context) -> bool {
+        true
+    }
 }